@@ -2,7 +2,7 @@ use proc_macro2::{TokenStream as TokenStream2, Ident};
 use syn::DeriveInput;
 
 
-use crate::{structs::DatatypeEndianness, types::int::IntegerType};
+use crate::{generate::{produce_async_impl, produce_bytes_impl, produce_fixed_decode_impl, BYTES_EXT, IO, WRITER}, structs::DatatypeEndianness, types::int::IntegerType};
 
 use super::structs::{DataEnumArgs, DataEnumArg};
 
@@ -33,6 +33,12 @@ pub(crate) fn produce(ast: &DeriveInput, args: &DataEnumArgs) -> TokenStream2 {
     let name = &ast.ident;
 
     let size = ty.size();  
+    let bytes_impl = produce_bytes_impl(name, &ast.generics, None, true, true);
+    let decode_impl = produce_fixed_decode_impl(name, &ast.generics, None);
+    let async_impl = match cfg!(feature = "async") {
+        true => produce_async_impl(name, &ast.generics, None, true, false, true, true),
+        false => quote! {},
+    };
     let ty_ident: Ident = (&ty).into(); 
 
     let from = format_ident!("from_{}", ty_ident);
@@ -61,24 +67,29 @@ pub(crate) fn produce(ast: &DeriveInput, args: &DataEnumArgs) -> TokenStream2 {
             
         
         impl crate::datatypes::Deserialize for #name {
-            fn deserialize(byte_stream: &mut std::io::Cursor<&[u8]>) -> std::io::Result<Self>
+            fn deserialize(byte_stream: &mut #IO::Cursor<&[u8]>) -> #IO::Result<Self>
             where
                 Self: Sized,
             {
-                use byteorder::{BigEndian, ReadBytesExt};
+                use byteorder::BigEndian;
+                use #BYTES_EXT::ReadBytesExt;
                 let value = #read.unwrap();
 
                 Ok(value)
             }
         }
 
-        impl crate::datatypes::Serialize for #name {
-            fn serialize(&self, writer: &mut std::io::BufWriter<std::fs::File>) -> std::io::Result<()>
+        impl crate::datatypes::WriteTo for #name {
+            fn write_to<W: #IO::Write + #IO::Seek>(&self, writer: &mut W) -> #IO::Result<()>
             {
-                use byteorder::{BigEndian, WriteBytesExt};
-                #write
+                Self::write_to(self, writer)
+            }
+        }
 
-                Ok(())
+        impl crate::datatypes::Serialize for #name {
+            fn serialize(&self, writer: &mut #WRITER) -> #IO::Result<()>
+            {
+                self.write_to(writer)
             }
     
             fn size(&self) -> u32
@@ -86,6 +97,31 @@ pub(crate) fn produce(ast: &DeriveInput, args: &DataEnumArgs) -> TokenStream2 {
                 #size
             }           
         }
+
+        impl #name {
+            /// Size in bytes of the serialized datatype
+            pub const SIZE: usize = #size as usize;
+
+            /// Serializes the datatype into any seekable writer
+            pub fn write_to<W: #IO::Write + #IO::Seek>(&self, writer: &mut W) -> #IO::Result<()>
+            {
+                use byteorder::BigEndian;
+                use #BYTES_EXT::WriteBytesExt;
+                #write
+
+                Ok(())
+            }
+        }
+
+        #bytes_impl
+
+        #decode_impl
+
+        #async_impl
+
+        impl crate::datatypes::FixedSize for #name {
+            const SIZE: usize = #name::SIZE;
+        }
         
         
     }
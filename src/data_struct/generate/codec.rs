@@ -0,0 +1,187 @@
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use proc_macro_error::emit_error;
+use syn::{Lifetime, Visibility};
+
+use crate::{
+    data_struct::parse::ItemStruct,
+    generate::IO,
+    structs::{CollectionBound, DatatypeAttribute, DatatypeAttributeType},
+    types::int::IntegerType,
+};
+
+// same default as the LengthDelimitedCodec of tokio-util
+const DEFAULT_MAX_FRAME_SIZE: usize = 8 * 1024 * 1024;
+
+// frames are either prefixed with their length in big endian or delimited by the datatype itself
+fn quote_decode_frame(name: &Ident, length: &Option<IntegerType>) -> TokenStream2 {
+    let too_large = format!("Frame of '{}' exceeds the maximum frame size", name);
+
+    let length = match length {
+        Some(length) => length,
+        None => return quote! {
+            match #name::decode(&src[..])? {
+                crate::datatypes::Decoded::Complete(value, consumed) => {
+                    let _ = src.split_to(consumed);
+                    Ok(Some(value))
+                }
+                crate::datatypes::Decoded::Incomplete { needed } => {
+                    let needed = needed.unwrap_or(1);
+                    if src.len().saturating_add(needed) > self.max_frame_size {
+                        return Err(#IO::Error::new(#IO::ErrorKind::InvalidData, #too_large));
+                    }
+
+                    src.reserve(needed);
+                    Ok(None)
+                }
+            }
+        },
+    };
+
+    let ty: Ident = length.into();
+    let prefix = length.size() as usize;
+    let mismatch = format!("Frame of '{}' does not match its length", name);
+
+    quote! {
+        if src.len() < #prefix {
+            src.reserve(#prefix - src.len());
+            return Ok(None);
+        }
+
+        let length = #ty::from_be_bytes(src[..#prefix].try_into().unwrap());
+        let length = usize::try_from(length)
+            .ok()
+            .filter(|length| *length <= self.max_frame_size)
+            .ok_or_else(|| #IO::Error::new(#IO::ErrorKind::InvalidData, #too_large))?;
+        if src.len() < #prefix + length {
+            src.reserve(#prefix + length - src.len());
+            return Ok(None);
+        }
+
+        let frame = src.split_to(#prefix + length);
+        let (value, consumed) = #name::from_bytes(&frame[#prefix..])?;
+        if consumed != length {
+            return Err(#IO::Error::new(#IO::ErrorKind::InvalidData, #mismatch));
+        }
+
+        Ok(Some(value))
+    }
+}
+
+fn quote_encode_frame(name: &Ident, length: &Option<IntegerType>) -> TokenStream2 {
+    let too_large = format!("Frame of '{}' exceeds the maximum frame size", name);
+
+    let (prefix, write_prefix) = match length {
+        Some(length) => {
+            let ty: Ident = length.into();
+            let prefix = length.size() as usize;
+            (
+                quote! { #prefix },
+                quote! {
+                    let length = #ty::try_from(size)
+                        .map_err(|_| #IO::Error::new(#IO::ErrorKind::InvalidInput, #too_large))?;
+                    dst[start..start + #prefix].copy_from_slice(&length.to_be_bytes());
+                },
+            )
+        }
+        None => (quote! { 0usize }, quote! {}),
+    };
+
+    quote! {
+        let size = crate::datatypes::Serialize::size(&item) as usize;
+        if size > self.max_frame_size {
+            return Err(#IO::Error::new(#IO::ErrorKind::InvalidInput, #too_large));
+        }
+
+        let start = dst.len();
+        dst.resize(start + #prefix + size, 0);
+        #write_prefix
+        let written = item.serialize_into(&mut dst[start + #prefix..])?;
+        dst.truncate(start + #prefix + written);
+
+        Ok(())
+    }
+}
+
+// tokio-util codec putting the datatype on framed streams
+pub(crate) fn produce_codec_impl(
+    datatype_struct: &ItemStruct,
+    lifetime: Option<&Lifetime>,
+    attrs: &[DatatypeAttribute],
+    length: &Option<IntegerType>,
+    max_frame_size: Option<usize>,
+) -> TokenStream2 {
+    let name = &datatype_struct.ident;
+    if cfg!(feature = "no_std") {
+        emit_error!(name.span(), "'codec' requires std and cannot be used with the 'no_std' feature");
+        return quote! {};
+    }
+
+    if let Some(lifetime) = lifetime {
+        emit_error!(lifetime, "'codec' requires a datatype that does not borrow from the input");
+        return quote! {};
+    }
+
+    // without a length prefix the end of a frame has to be known from the datatype itself
+    let until_eof = attrs
+        .iter()
+        .find(|x| matches!(x.ty, DatatypeAttributeType::Collection(_, _, CollectionBound::Eof, _)));
+    if let (None, Some(attribute)) = (length, until_eof) {
+        emit_error!(attribute.name.span(), "'{}' reads until the end of the input, use 'codec(length_ty = ..)' to prefix frames with their length", attribute.name);
+        return quote! {};
+    }
+
+    let visibility: &Visibility = &datatype_struct.visibility;
+    let codec = format_ident!("{}Codec", name);
+    let max_frame_size = max_frame_size.unwrap_or(DEFAULT_MAX_FRAME_SIZE);
+    let decode = quote_decode_frame(name, length);
+    let encode = quote_encode_frame(name, length);
+    let doc = format!("Encodes and decodes [`{}`] frames on framed streams", name);
+
+    quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, Copy)]
+        #visibility struct #codec {
+            max_frame_size: usize,
+        }
+
+        impl #codec {
+            pub const DEFAULT_MAX_FRAME_SIZE: usize = #max_frame_size;
+
+            pub fn new() -> Self {
+                #codec { max_frame_size: #codec::DEFAULT_MAX_FRAME_SIZE }
+            }
+
+            /// Frames exceeding `max_frame_size` bytes are rejected before they are buffered
+            pub fn with_max_frame_size(max_frame_size: usize) -> Self {
+                #codec { max_frame_size }
+            }
+
+            pub fn max_frame_size(&self) -> usize {
+                self.max_frame_size
+            }
+        }
+
+        impl Default for #codec {
+            fn default() -> Self {
+                #codec::new()
+            }
+        }
+
+        impl tokio_util::codec::Decoder for #codec {
+            type Item = #name;
+            type Error = #IO::Error;
+
+            fn decode(&mut self, src: &mut bytes::BytesMut) -> #IO::Result<Option<#name>> {
+                #decode
+            }
+        }
+
+        impl tokio_util::codec::Encoder<#name> for #codec {
+            type Error = #IO::Error;
+
+            fn encode(&mut self, item: #name, dst: &mut bytes::BytesMut) -> #IO::Result<()> {
+                #encode
+            }
+        }
+    }
+}
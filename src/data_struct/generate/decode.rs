@@ -0,0 +1,101 @@
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use syn::{Generics, Lifetime};
+
+use crate::{
+    data_struct::generate::{
+        deserialize::{quote_deserialize_attributes, quote_deserialize_body},
+        serialize::primitive_size,
+        size::quote_static_size,
+    },
+    generate::IO,
+    structs::{CollectionBound, DatatypeAttribute, DatatypeAttributeType, LengthUnit, Padding, Storage},
+};
+
+// number of bytes the attribute occupies if it is known before reading it, collections with a
+// length use the already deserialized length
+fn quote_known_size(attribute: &DatatypeAttribute) -> Option<TokenStream2> {
+    match &attribute.ty {
+        DatatypeAttributeType::Padding(Padding::Align(_)) => None,
+        DatatypeAttributeType::Collection(ty, None, bound, storage) => {
+            let element_size = match storage {
+                Storage::Borrowed => 1,
+                _ => primitive_size(ty)? as usize,
+            };
+            let (length, unit) = match bound {
+                CollectionBound::Prefix(unit) => {
+                    let var_name = format_ident!("{}_len", attribute.name);
+                    (quote! { #var_name }, unit)
+                }
+                CollectionBound::Field(field, unit) => (quote! { #field }, unit),
+                CollectionBound::Expr(expr, unit) => (quote! { (#expr) }, unit),
+                CollectionBound::Terminator(_) | CollectionBound::Eof => return None,
+            };
+            let element_size = match unit {
+                LengthUnit::Elements => element_size,
+                LengthUnit::Bytes => 1,
+            };
+
+            // invalid lengths are reported by the deserialization itself
+            Some(quote! { usize::try_from(#length).ok().and_then(|length| length.checked_mul(#element_size)) })
+        }
+        _ => quote_static_size(attribute, false).map(|size| quote! { Some((#size) as usize) }),
+    }
+}
+
+// resumable decoding of partially received input. In front of every attribute whose size is known
+// before reading it, the remaining input is checked so that the amount of missing bytes can be
+// reported, everything else that runs out of input is incomplete without a known amount.
+pub(crate) fn produce_decode_impl(
+    name: &Ident,
+    generics: &Generics,
+    lifetime: Option<&Lifetime>,
+    attrs: &[DatatypeAttribute],
+    align_to_stream: bool,
+) -> TokenStream2 {
+    let reads = quote_deserialize_attributes(attrs, align_to_stream);
+    let reads: Vec<TokenStream2> = attrs
+        .iter()
+        .zip(reads)
+        .map(|(attribute, read)| match quote_known_size(attribute) {
+            Some(size) if attribute.address.is_none() => quote! {
+                if let Some(end) = #size.and_then(|size| (byte_stream.position() as usize).checked_add(size)) {
+                    if end > byte_stream.get_ref().len() {
+                        needed = Some(end - byte_stream.get_ref().len());
+                        return Err(#IO::Error::from(#IO::ErrorKind::UnexpectedEof));
+                    }
+                }
+                #read
+            },
+            _ => read,
+        })
+        .collect();
+    let body = quote_deserialize_body(name, attrs, &reads, align_to_stream);
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let bytes = match lifetime {
+        Some(lifetime) => quote! { &#lifetime [u8] },
+        None => quote! { &[u8] },
+    };
+
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Decodes the datatype from the start of `bytes` without consuming anything if the input is
+            /// incomplete, `needed` is the least amount of missing bytes if it is known
+            pub fn decode(bytes: #bytes) -> #IO::Result<crate::datatypes::Decoded<Self>> {
+                #[allow(unused_mut)]
+                let mut needed: Option<usize> = None;
+                let mut cursor = #IO::Cursor::new(bytes);
+                #[allow(unused_variables, unused_mut)]
+                let mut decode = |byte_stream: &mut #IO::Cursor<#bytes>| -> #IO::Result<Self> {
+                    #body
+                };
+
+                match decode(&mut cursor) {
+                    Ok(value) => Ok(crate::datatypes::Decoded::Complete(value, cursor.position() as usize)),
+                    Err(error) if error.kind() == #IO::ErrorKind::UnexpectedEof => Ok(crate::datatypes::Decoded::Incomplete { needed }),
+                    Err(error) => Err(error),
+                }
+            }
+        }
+    }
+}
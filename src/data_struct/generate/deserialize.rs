@@ -1,6 +1,7 @@
-use proc_macro2::{TokenStream as TokenStream2, Ident};
+use proc_macro2::{Group, Literal, TokenStream as TokenStream2, TokenTree, Ident};
+use syn::{Generics, Lifetime};
 
-use crate::{structs::{DatatypeAttributeType, DatatypeAttribute}, types::{int::IntegerType, float::FloatType}};
+use crate::{data_struct::{checksum_range, generate::{checksum_ranges, last_alignment, offset_ident, quote_alignment, quote_checksum, quote_pow2, serialize::primitive_size, validate::quote_constraints}}, generate::{quote_message, ALLOC, BYTES_EXT, CORE, IO, STRING, VEC, VEC_MACRO}, structs::{Address, CollectionBound, DatatypeAttributeType, DatatypeAttribute, LengthUnit, OffsetBase, Padding, Storage}, types::{checksum::ChecksumAlgorithm, decimal::DecimalFormat, int::IntegerType, float::{FloatEncoding, FloatType}, scaled::Scaling, string::{Charset, StringFormat, TextEncoding, WINDOWS_1252}, varint::VarintEncoding}};
 
 fn quote_deserialize_primitive_integer(t: &IntegerType, name: &Ident, endianness: &TokenStream2) -> TokenStream2
 {
@@ -13,6 +14,89 @@ fn quote_deserialize_primitive_integer(t: &IntegerType, name: &Ident, endianness
     }   
 }
 
+// reads a varint and evaluates to a value of type t, malformed or too large values are rejected
+fn quote_deserialize_varint(encoding: &VarintEncoding, t: &IntegerType, name: &Ident) -> TokenStream2
+{
+    let ty: Ident = t.into();
+    let max_size = encoding.max_size(t);
+    let too_long = format!("Malformed varint for '{}': more than {} bytes", name, max_size);
+    let overflow = format!("Varint for '{}' does not fit into {}", name, ty);
+
+    // the last group of 128 bit values may only partially fit, signed LEB128 pads it with the sign bit
+    let check_overflow = match (encoding, t.is_signed()) {
+        (VarintEncoding::Leb128, true) => quote! {
+            if shift + 7 > 128 && group >> (127 - shift) != 0 && group >> (127 - shift) != 0x7f >> (127 - shift) {
+                return Err(#IO::Error::new(#IO::ErrorKind::InvalidData, #overflow));
+            }
+        },
+        _ => quote! {
+            if shift + 7 > 128 && group >> (128 - shift) != 0 {
+                return Err(#IO::Error::new(#IO::ErrorKind::InvalidData, #overflow));
+            }
+        },
+    };
+
+    let read = match encoding {
+        VarintEncoding::Vlq => quote! {
+            let mut value: u128 = 0;
+            let mut count = 0u32;
+            loop {
+                if count >= #max_size {
+                    return Err(#IO::Error::new(#IO::ErrorKind::InvalidData, #too_long));
+                }
+                let byte = byte_stream.read_u8()?;
+                if value >> 121 != 0 {
+                    return Err(#IO::Error::new(#IO::ErrorKind::InvalidData, #overflow));
+                }
+                value = (value << 7) | (byte & 0x7f) as u128;
+                count += 1;
+                if byte & 0x80 == 0 {
+                    break;
+                }
+            }
+        },
+        _ => quote! {
+            let mut value: u128 = 0;
+            let mut shift = 0u32;
+            let mut byte;
+            loop {
+                if shift >= #max_size * 7 {
+                    return Err(#IO::Error::new(#IO::ErrorKind::InvalidData, #too_long));
+                }
+                byte = byte_stream.read_u8()?;
+                let group = (byte & 0x7f) as u128;
+                #check_overflow
+                value |= group << shift;
+                shift += 7;
+                if byte & 0x80 == 0 {
+                    break;
+                }
+            }
+        },
+    };
+
+    let convert = match (encoding, t.is_signed()) {
+        (VarintEncoding::Zigzag, _) => quote! {
+            let value = ((value >> 1) as i128) ^ -((value & 1) as i128);
+        },
+        (VarintEncoding::Leb128, true) => quote! {
+            let mut value = value as i128;
+            if shift < 128 && byte & 0x40 != 0 {
+                value |= -1i128 << shift;
+            }
+        },
+        _ => quote! {},
+    };
+
+    quote! {
+        {
+            #read
+            #convert
+            #ty::try_from(value).map_err(|_| #IO::Error::new(#IO::ErrorKind::InvalidData, #overflow))?
+        }
+    }
+}
+
 fn quote_deserialize_primitive_float(t: &FloatType, name: &Ident, endianness: &TokenStream2) -> TokenStream2
 {
     let ty : &str = t.clone().into();
@@ -21,96 +105,1235 @@ fn quote_deserialize_primitive_float(t: &FloatType, name: &Ident, endianness: &T
     quote! { let #name = byte_stream.#read::<byteorder::#endianness>()?; }     
 }
 
-fn quote_deserialize_primitive_struct(ty: &String, name: &Ident) -> TokenStream2
+// reads the raw integer and converts it to a float of type t
+fn quote_deserialize_scaled(t: &FloatType, scaling: &Scaling, endianness: &TokenStream2) -> TokenStream2
 {
+    let ty: &str = t.clone().into();
     let ty: TokenStream2 = ty.parse().unwrap();
+    let raw = quote_deserialize_integer(&scaling.raw, endianness);
+    let scale = Literal::f64_suffixed(scaling.scale);
+    let offset = Literal::f64_suffixed(scaling.offset);
 
-    quote! { let #name = #ty::deserialize(byte_stream)?; }          
+    quote! { ((#raw) as f64 * #scale + #offset) as #ty }
 }
 
-fn quote_deserialize_primitive_collection(ty: &String, size: &Option<u8>, name: &Ident, attribute: &DatatypeAttribute) -> TokenStream2
+// reads a float in the given encoding and converts it to type t
+fn quote_deserialize_encoded_float(t: &FloatType, encoding: &FloatEncoding, endianness: &TokenStream2) -> TokenStream2
 {
-    match size {
-        Some(size) => quote! {
-            let mut #name: [#ty; #size];
-            for i in 0..#size {
-                #name[i].push(#ty::deserialize(byte_stream)?);
+    let ty: &str = t.clone().into();
+    let ty: TokenStream2 = ty.parse().unwrap();
+
+    let read = match encoding {
+        FloatEncoding::F16 => quote! {
+            let bits = byte_stream.read_u16::<byteorder::#endianness>()?;
+            let sign = ((bits as u32) & 0x8000) << 16;
+            let exponent = ((bits >> 10) & 0x1f) as u32;
+            let mantissa = (bits & 0x3ff) as u32;
+            let value = match exponent {
+                // subnormal numbers are multiples of 2^-24
+                0 => {
+                    let value = mantissa as f32 / 16777216.0;
+                    if sign != 0 { -value } else { value }
+                }
+                // infinities and NaNs keep their payload
+                0x1f => f32::from_bits(sign | 0x7f800000 | (mantissa << 13)),
+                _ => f32::from_bits(sign | ((exponent + 112) << 23) | (mantissa << 13)),
+            };
+        },
+        FloatEncoding::Bf16 => quote! {
+            let value = f32::from_bits((byte_stream.read_u16::<byteorder::#endianness>()? as u32) << 16);
+        },
+        FloatEncoding::Ibm32 | FloatEncoding::Ibm64 => {
+            let (read, fraction_bits) = match encoding {
+                FloatEncoding::Ibm32 => (quote! { byte_stream.read_u32::<byteorder::#endianness>()? as u64 }, 24i32),
+                _ => (quote! { byte_stream.read_u64::<byteorder::#endianness>()? }, 56i32),
+            };
+            let power = quote_pow2(&quote! { 4 * (((bits >> #fraction_bits) & 0x7f) as i32 - 64) });
+            quote! {
+                let bits = #read;
+                // fraction within [0, 1) multiplied by 16^(characteristic - 64)
+                let fraction = (bits & ((1u64 << #fraction_bits) - 1)) as f64 / (1u64 << #fraction_bits) as f64;
+                let value = fraction * #power;
+                let value = if bits >> (#fraction_bits + 7) != 0 { -value } else { value };
+            }
+        }
+        FloatEncoding::VaxF | FloatEncoding::VaxG => {
+            // VAX floats consist of little endian 16 bit words starting with the most significant one
+            let (words, fraction_bits, exponent_bias) = match encoding {
+                FloatEncoding::VaxF => (2u32, 23i32, 128i32),
+                _ => (4u32, 52i32, 1024i32),
+            };
+            let sign_shift = words * 16 - 1;
+            let exponent_mask = (1u64 << (sign_shift - fraction_bits as u32)) - 1;
+            let power = quote_pow2(&quote! { exponent - #exponent_bias });
+            quote! {
+                let mut bits = 0u64;
+                for _ in 0..#words {
+                    bits = (bits << 16) | byte_stream.read_u16::<byteorder::LittleEndian>()? as u64;
+                }
+                let negative = bits >> #sign_shift != 0;
+                let exponent = ((bits >> #fraction_bits) & #exponent_mask) as i32;
+                let value = match (exponent, negative) {
+                    (0, false) => 0.0,
+                    // reserved operand
+                    (0, true) => f64::NAN,
+                    _ => {
+                        // 0.1fff multiplied by 2^(exponent - bias)
+                        let fraction = ((bits & ((1u64 << #fraction_bits) - 1)) | (1u64 << #fraction_bits)) as f64 / (1u64 << (#fraction_bits + 1)) as f64;
+                        let value = fraction * #power;
+                        if negative { -value } else { value }
+                    }
+                };
+            }
+        }
+    };
+
+    quote! {
+        {
+            #read
+            value as #ty
+        }
+    }
+}
+
+// reads the decimal digits of an integer of type t, invalid digits and too large values are rejected
+fn quote_deserialize_decimal(t: &IntegerType, format: &DecimalFormat, name: &Ident, endianness: &TokenStream2) -> TokenStream2
+{
+    let ty: Ident = t.into();
+    let width = format.size() as usize;
+    let overflow = format!("'{}' does not fit into {}", name, ty);
+
+    let digits = match format {
+        DecimalFormat::Bcd(_) => {
+            let message = quote_message(
+                &format!("'{}' contains the invalid BCD byte {{:#04x}}", name),
+                quote! { byte },
+                &format!("'{}' contains an invalid BCD byte", name),
+            );
+            // the least significant byte comes first in little endian
+            let reverse = match endianness.to_string().as_str() {
+                "LittleEndian" => quote! { bytes.reverse(); },
+                _ => quote! {},
+            };
+            quote! {
+                #reverse
+                for byte in bytes {
+                    if byte >> 4 > 9 || byte & 0x0f > 9 {
+                        return Err(#IO::Error::new(#IO::ErrorKind::InvalidData, #message));
+                    }
+                    value = value * 100 + ((byte >> 4) * 10 + (byte & 0x0f)) as u128;
+                }
+            }
+        }
+        DecimalFormat::Ascii(_) => {
+            let message = quote_message(
+                &format!("'{}' contains the invalid digit {{:?}}", name),
+                quote! { byte as char },
+                &format!("'{}' contains an invalid digit", name),
+            );
+            quote! {
+                for byte in bytes {
+                    if !byte.is_ascii_digit() {
+                        return Err(#IO::Error::new(#IO::ErrorKind::InvalidData, #message));
+                    }
+                    value = value
+                        .checked_mul(10)
+                        .and_then(|value| value.checked_add((byte - b'0') as u128))
+                        .ok_or_else(|| #IO::Error::new(#IO::ErrorKind::InvalidData, #overflow))?;
+                }
+            }
+        }
+    };
+
+    quote! {
+        {
+            let mut bytes = [0u8; #width];
+            #IO::Read::read_exact(byte_stream, &mut bytes)?;
+            let mut value: u128 = 0;
+            #digits
+            #ty::try_from(value).map_err(|_| #IO::Error::new(#IO::ErrorKind::InvalidData, #overflow))?
+        }
+    }
+}
+
+fn quote_deserialize_integer(t: &IntegerType, endianness: &TokenStream2) -> TokenStream2
+{
+    let ty : &str = t.clone().into();
+    let read = format_ident!("read_{}", ty);
+
+    match t{
+        IntegerType::U8 | IntegerType::I8 => quote! { byte_stream.#read()? },
+        _ => quote! { byte_stream.#read::<byteorder::#endianness>()? }
+    }
+}
+
+// maps the byte `byte` of a single byte charset to a char
+fn quote_decode_single_byte(encoding: &TextEncoding, name: &Ident) -> TokenStream2
+{
+    let charset: &str = (&encoding.charset).into();
+    let map = match encoding.charset {
+        Charset::Ascii => quote! { if byte < 0x80 { Some(byte as char) } else { None } },
+        Charset::Windows1252 => {
+            let table = WINDOWS_1252.iter();
+            quote! {
+                match byte {
+                    0x80..=0x9f => char::from_u32([#(#table),*][(byte - 0x80) as usize] as u32),
+                    _ => Some(byte as char),
+                }
             }
         },
-        None =>  {
-            let var_name = format_ident!("{}_len", attribute.name);
-            let ty = format_ident!("{}", ty);
+        _ => quote! { Some(byte as char) },
+    };
+
+    match encoding.lossy {
+        true => quote! { (#map).unwrap_or(char::REPLACEMENT_CHARACTER) },
+        false => {
+            let message = format!("'{}' is not valid {}", name, charset);
+            quote! {
+                (#map).ok_or_else(|| #IO::Error::new(#IO::ErrorKind::InvalidData, #message))?
+            }
+        }
+    }
+}
+
+// converts the raw `bytes` into a String
+fn quote_decode_string(encoding: &TextEncoding, name: &Ident) -> TokenStream2
+{
+    let charset: &str = (&encoding.charset).into();
+    let message = format!("String '{}' is not valid {}", name, charset);
+
+    match (&encoding.charset, encoding.lossy) {
+        (Charset::Utf8, true) => quote! { #STRING::from_utf8_lossy(&bytes).into_owned() },
+        (Charset::Utf8, false) => quote! {
+            #STRING::from_utf8(bytes)
+                .map_err(|_| #IO::Error::new(#IO::ErrorKind::InvalidData, #message))?
+        },
+        (Charset::Ascii | Charset::Latin1 | Charset::Windows1252, _) => {
+            let map = quote_decode_single_byte(encoding, name);
+            quote! {
+                bytes
+                    .iter()
+                    .map(|byte| -> #IO::Result<char> {
+                        let byte = *byte;
+                        let character = #map;
+                        Ok(character)
+                    })
+                    .collect::<#IO::Result<#STRING>>()?
+            }
+        },
+        (Charset::Utf16Le | Charset::Utf16Be, lossy) => {
+            let from_bytes = match encoding.charset {
+                Charset::Utf16Le => quote! { u16::from_le_bytes },
+                _ => quote! { u16::from_be_bytes },
+            };
+            let units = quote! {
+                char::decode_utf16(bytes.chunks_exact(2).map(|unit| #from_bytes([unit[0], unit[1]])))
+            };
+
+            match lossy {
+                true => quote! {
+                    {
+                        let mut value: #STRING = #units
+                            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                            .collect();
+                        if bytes.len() % 2 != 0 {
+                            value.push(char::REPLACEMENT_CHARACTER);
+                        }
+                        value
+                    }
+                },
+                false => quote! {
+                    {
+                        if bytes.len() % 2 != 0 {
+                            return Err(#IO::Error::new(#IO::ErrorKind::InvalidData, #message));
+                        }
+                        #units
+                            .collect::<Result<#STRING, _>>()
+                            .map_err(|_| #IO::Error::new(#IO::ErrorKind::InvalidData, #message))?
+                    }
+                },
+            }
+        },
+    }
+}
+
+// pod datatypes read all elements with a single copy through their inherent read_many which takes
+// precedence over this trait, every other element is deserialized one by one
+fn quote_read_many_fallback() -> TokenStream2
+{
+    let remaining = quote_remaining();
+
+    quote! {
+        trait ReadMany: Sized {
+            fn read_many(byte_stream: &mut #IO::Cursor<&[u8]>, count: usize) -> #IO::Result<#VEC<Self>>;
+        }
+        impl<T: crate::datatypes::Deserialize> ReadMany for T {
+            fn read_many(byte_stream: &mut #IO::Cursor<&[u8]>, count: usize) -> #IO::Result<#VEC<Self>> {
+                let mut entries = #VEC::with_capacity(count.min(#remaining));
+                for _ in 0..count {
+                    entries.push(T::deserialize(byte_stream)?);
+                }
+
+                Ok(entries)
+            }
+        }
+    }
+}
+
+// amount of bytes left in the input. Lengths read from the input only preallocate up to it so that a
+// malformed length fails with an error once the input runs out instead of allocating beforehand
+fn quote_remaining() -> TokenStream2
+{
+    quote! { byte_stream.get_ref().len().saturating_sub(byte_stream.position() as usize) }
+}
+
+// borrows the next `length` bytes from the input as `&'a [u8]`
+fn quote_borrow_bytes(length: &TokenStream2, name: &Ident) -> TokenStream2
+{
+    let message = format!("'{}' is longer than the remaining input", name);
+
+    quote! {
+        {
+            let data: &[u8] = *byte_stream.get_ref();
+            let start = byte_stream.position() as usize;
+            let end = start
+                .checked_add(#length)
+                .filter(|end| *end <= data.len())
+                .ok_or_else(|| #IO::Error::new(#IO::ErrorKind::UnexpectedEof, #message))?;
+            byte_stream.set_position(end as u64);
+            &data[start..end]
+        }
+    }
+}
+
+// borrows the bytes up to the terminator and skips the terminator
+fn quote_borrow_terminated(terminator: &[u8], message: &str) -> TokenStream2
+{
+    let unit_size = terminator.len();
+
+    quote! {
+        {
+            let data: &[u8] = *byte_stream.get_ref();
+            let start = (byte_stream.position() as usize).min(data.len());
+            let length = data[start..]
+                .chunks_exact(#unit_size)
+                .position(|unit| unit == [#(#terminator),*])
+                .ok_or_else(|| #IO::Error::new(#IO::ErrorKind::UnexpectedEof, #message))?
+                * #unit_size;
+            byte_stream.set_position((start + length + #unit_size) as u64);
+            &data[start..start + length]
+        }
+    }
+}
+
+// string that borrows its bytes from the input, Cow<str> falls back to an owned String if the
+// bytes need to be decoded
+fn quote_deserialize_borrowed_string(format: &StringFormat, encoding: &TextEncoding, storage: &Storage, name: &Ident, endianness: &TokenStream2) -> TokenStream2
+{
+    let unit_size = encoding.charset.unit_size();
+    let bytes = match format {
+        StringFormat::Terminated(terminator) => {
+            let message = format!("String '{}' is missing its terminator {:#04x}", name, terminator);
+            quote_borrow_terminated(&encoding.charset.unit(*terminator), &message)
+        },
+        StringFormat::Prefixed(t, varint) => {
+            let length = match varint {
+                Some(varint) => quote_deserialize_varint(varint, t, name),
+                None => quote_deserialize_integer(t, endianness),
+            };
+            let length = match encoding.count_units {
+                true => quote! { usize::try_from(#length).ok().and_then(|length| length.checked_mul(#unit_size)) },
+                false => quote! { usize::try_from(#length).ok() },
+            };
+            let message = format!("Invalid length prefix for string '{}'", name);
+            let borrow = quote_borrow_bytes(&quote! { length }, name);
+
+            quote! {
+                {
+                    let length = #length
+                        .ok_or_else(|| #IO::Error::new(#IO::ErrorKind::InvalidData, #message))?;
+                    #borrow
+                }
+            }
+        },
+        StringFormat::Fixed { width, pad, .. } => {
+            let pad_unit = encoding.charset.unit(*pad);
+            let borrow = quote_borrow_bytes(&quote! { #width }, name);
+            let strip = match pad {
+                0 => quote! {
+                    match bytes.chunks_exact(#unit_size).position(|unit| unit == [#(#pad_unit),*]) {
+                        Some(end) => &bytes[..end * #unit_size],
+                        None => bytes,
+                    }
+                },
+                _ => quote! {
+                    {
+                        let mut bytes = bytes;
+                        while bytes.ends_with(&[#(#pad_unit),*]) {
+                            bytes = &bytes[..bytes.len() - #unit_size];
+                        }
+                        bytes
+                    }
+                },
+            };
+
+            quote! {
+                {
+                    let bytes: &[u8] = #borrow;
+                    #strip
+                }
+            }
+        },
+    };
+
+    let message = format!("String '{}' is not valid utf8", name);
+    let from_utf8 = quote! {
+        #CORE::str::from_utf8(bytes)
+            .map_err(|_| #IO::Error::new(#IO::ErrorKind::InvalidData, #message))?
+    };
+    let decode = match (storage, &encoding.charset, encoding.lossy) {
+        (Storage::Cow, Charset::Utf8, true) => quote! { #STRING::from_utf8_lossy(bytes) },
+        (Storage::Cow, Charset::Utf8, false) => quote! { #ALLOC::borrow::Cow::Borrowed(#from_utf8) },
+        (Storage::Cow, _, _) => {
+            let decode = quote_decode_string(encoding, name);
+            quote! { #ALLOC::borrow::Cow::Owned(#decode) }
+        },
+        _ => from_utf8,
+    };
+
+    quote! {
+        let #name = {
+            let bytes: &[u8] = #bytes;
+            #decode
+        };
+    }
+}
+
+fn quote_deserialize_primitive_string(format: &StringFormat, encoding: &TextEncoding, storage: &Storage, name: &Ident, endianness: &TokenStream2) -> TokenStream2
+{
+    // bounded strings copy the borrowed input which is only decoded into an owned String if needed
+    if let Storage::Bounded(container) = storage {
+        let read = quote_deserialize_borrowed_string(format, encoding, &Storage::Cow, name, endianness);
+        let container: TokenStream2 = container.parse().unwrap();
+        let message = format!("String '{}' exceeds its capacity", name);
+
+        return quote! {
+            #read
+            let #name = {
+                let mut value = <#container>::new();
+                value
+                    .push_str(&#name)
+                    .map_err(|_| #IO::Error::new(#IO::ErrorKind::InvalidData, #message))?;
+                value
+            };
+        };
+    }
+
+    if *storage != Storage::Owned {
+        return quote_deserialize_borrowed_string(format, encoding, storage, name, endianness);
+    }
+
+    let unit_size = encoding.charset.unit_size();
+    let read = match format {
+        StringFormat::Terminated(terminator) => {
+            let message = format!("String '{}' is missing its terminator {:#04x}", name, terminator);
+
+            match unit_size {
+                1 => quote! {
+                    let mut bytes = #VEC::new();
+                    #IO::BufRead::read_until(byte_stream, #terminator, &mut bytes)?;
+                    if bytes.pop() != Some(#terminator) {
+                        return Err(#IO::Error::new(#IO::ErrorKind::UnexpectedEof, #message));
+                    }
+                },
+                _ => {
+                    let terminator = encoding.charset.unit(*terminator);
+                    quote! {
+                        let mut bytes = #VEC::new();
+                        loop {
+                            let mut unit = [0u8; #unit_size];
+                            #IO::Read::read_exact(byte_stream, &mut unit)
+                                .map_err(|_| #IO::Error::new(#IO::ErrorKind::UnexpectedEof, #message))?;
+                            if unit == [#(#terminator),*] {
+                                break;
+                            }
+                            bytes.extend_from_slice(&unit);
+                        }
+                    }
+                }
+            }
+        },
+        StringFormat::Prefixed(t, varint) => {
+            let length = match varint {
+                Some(varint) => quote_deserialize_varint(varint, t, name),
+                None => quote_deserialize_integer(t, endianness),
+            };
+            let length = match encoding.count_units {
+                true => quote! { usize::try_from(#length).ok().and_then(|length| length.checked_mul(#unit_size)) },
+                false => quote! { usize::try_from(#length).ok() },
+            };
+            let message = format!("Invalid length prefix for string '{}'", name);
+            let too_short = format!("String '{}' is longer than the remaining input", name);
+            let remaining = quote_remaining();
 
+            // the length is checked before allocating as it comes from the input
+            quote! {
+                let length = #length
+                    .ok_or_else(|| #IO::Error::new(#IO::ErrorKind::InvalidData, #message))?;
+                if length > #remaining {
+                    return Err(#IO::Error::new(#IO::ErrorKind::UnexpectedEof, #too_short));
+                }
+                let mut bytes = #VEC_MACRO![0u8; length];
+                #IO::Read::read_exact(byte_stream, &mut bytes)?;
+            }
+        },
+        StringFormat::Fixed { width, pad, .. } => {
+            let pad_unit = encoding.charset.unit(*pad);
+            let strip = match pad {
+                0 => quote! {
+                    if let Some(end) = bytes.chunks_exact(#unit_size).position(|unit| unit == [#(#pad_unit),*]) {
+                        bytes.truncate(end * #unit_size);
+                    }
+                },
+                _ => quote! {
+                    while bytes.ends_with(&[#(#pad_unit),*]) {
+                        bytes.truncate(bytes.len() - #unit_size);
+                    }
+                },
+            };
+
+            quote! {
+                let mut bytes = #VEC_MACRO![0u8; #width];
+                #IO::Read::read_exact(byte_stream, &mut bytes)?;
+                #strip
+            }
+        },
+    };
+
+    let decode = quote_decode_string(encoding, name);
+
+    quote! {
+        let #name = {
+            #read
+            #decode
+        };
+    }
+}
+
+fn quote_deserialize_primitive_char(encoding: &TextEncoding, name: &Ident) -> TokenStream2
+{
+    let charset: &str = (&encoding.charset).into();
+    let fallback = match encoding.lossy {
+        true => quote! { .unwrap_or(char::REPLACEMENT_CHARACTER) },
+        false => {
+            let message = format!("'{}' is not a valid {} character", name, charset);
+            quote! { .ok_or_else(|| #IO::Error::new(#IO::ErrorKind::InvalidData, #message))? }
+        }
+    };
+
+    let read = match encoding.charset {
+        Charset::Utf8 => quote! {
+            let mut buffer = [0u8; 4];
+            buffer[0] = byte_stream.read_u8()?;
+            let length = match buffer[0] {
+                0x00..=0x7f => 1,
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                0xf0..=0xf7 => 4,
+                _ => 0,
+            };
+            let value = match length {
+                0 => None,
+                _ => {
+                    #IO::Read::read_exact(byte_stream, &mut buffer[1..length])?;
+                    #CORE::str::from_utf8(&buffer[..length]).ok().and_then(|value| value.chars().next())
+                }
+            };
+            value #fallback
+        },
+        Charset::Ascii | Charset::Latin1 | Charset::Windows1252 => {
+            let map = quote_decode_single_byte(encoding, name);
+            quote! {
+                let byte = byte_stream.read_u8()?;
+                #map
+            }
+        },
+        Charset::Utf16Le | Charset::Utf16Be => {
+            let endianness = match encoding.charset {
+                Charset::Utf16Le => quote! { LittleEndian },
+                _ => quote! { BigEndian },
+            };
             quote! {
+                let mut units = [byte_stream.read_u16::<byteorder::#endianness>()?, 0];
+                let count = match (0xd800..0xdc00).contains(&units[0]) {
+                    true => {
+                        units[1] = byte_stream.read_u16::<byteorder::#endianness>()?;
+                        2
+                    }
+                    false => 1,
+                };
+                char::decode_utf16(units[..count].iter().copied()).next().and_then(|value| value.ok()) #fallback
+            }
+        },
+    };
+
+    quote! {
+        let #name = {
+            #read
+        };
+    }
+}
+
+// datatypes with a lifetime borrow from the input and can therefore only be read with `read_from`
+fn quote_deserialize_datatype(ty: &str, stream: &TokenStream2) -> TokenStream2
+{
+    let borrowed = ty.contains('\'');
+    let ty: TokenStream2 = ty.parse().unwrap();
+
+    match borrowed {
+        true => quote! { <#ty>::read_from(#stream) },
+        false => quote! { #ty::deserialize(#stream) },
+    }
+}
+
+fn quote_deserialize_primitive_struct(ty: &str, name: &Ident) -> TokenStream2
+{
+    let read = quote_deserialize_datatype(ty, &quote! { byte_stream });
+
+    quote! { let #name = #read?; }
+}
+
+// reads a single collection element from `stream`, primitives and Strings are read directly, everything
+// else is expected to be a Datatype
+fn quote_deserialize_element(ty: &String, attribute: &DatatypeAttribute, stream: &TokenStream2, endianness: &TokenStream2) -> TokenStream2
+{
+    // the string is read within a closure so that its errors are returned like the ones of other elements
+    if let Some((format, encoding)) = &attribute.element_string {
+        let name = &attribute.name;
+        let read = quote_deserialize_primitive_string(format, encoding, &Storage::Owned, name, endianness);
+        return quote! {
+            (|byte_stream: &mut #IO::Cursor<&[u8]>| -> #IO::Result<#STRING> {
+                #read
+                Ok(#name)
+            })(#stream)
+        };
+    }
+
+    if let Ok(t) = IntegerType::try_from(ty.as_str()) {
+        let read = format_ident!("read_{}", ty);
+        return match t {
+            IntegerType::U8 | IntegerType::I8 => quote! { #stream.#read() },
+            _ => quote! { #stream.#read::<byteorder::#endianness>() },
+        };
+    }
+
+    if FloatType::try_from(ty.as_str()).is_ok() {
+        let read = format_ident!("read_{}", ty);
+        return quote! { #stream.#read::<byteorder::#endianness>() };
+    }
+
+    quote_deserialize_datatype(ty, stream)
+}
+
+// byte slice borrowed from the input
+fn quote_deserialize_borrowed_bytes(bound: &CollectionBound, name: &Ident) -> TokenStream2
+{
+    let var_name = format_ident!("{}_len", name);
+    let length = match bound {
+        CollectionBound::Prefix(_) => quote! { #var_name },
+        CollectionBound::Field(field, _) => quote! { #field },
+        CollectionBound::Expr(expr, _) => quote! { (#expr) },
+        CollectionBound::Terminator(terminator) => {
+            let message = format!("'{}' is missing its terminator", name);
+            return quote! {
+                let #name: &[u8] = {
+                    let terminator: u8 = #terminator;
+                    let data: &[u8] = *byte_stream.get_ref();
+                    let start = (byte_stream.position() as usize).min(data.len());
+                    let length = data[start..]
+                        .iter()
+                        .position(|byte| *byte == terminator)
+                        .ok_or_else(|| #IO::Error::new(#IO::ErrorKind::UnexpectedEof, #message))?;
+                    byte_stream.set_position((start + length + 1) as u64);
+                    &data[start..start + length]
+                };
+            };
+        }
+        CollectionBound::Eof => {
+            return quote! {
+                let #name: &[u8] = {
+                    let data: &[u8] = *byte_stream.get_ref();
+                    let start = (byte_stream.position() as usize).min(data.len());
+                    byte_stream.set_position(data.len() as u64);
+                    &data[start..]
+                };
+            };
+        }
+    };
+    let message = format!("Invalid length for '{}'", name);
+    let borrow = quote_borrow_bytes(&quote! { length }, name);
+
+    quote! {
+        let #name: &[u8] = {
+            let length = usize::try_from(#length)
+                .map_err(|_| #IO::Error::new(#IO::ErrorKind::InvalidData, #message))?;
+            #borrow
+        };
+    }
+}
+
+fn quote_deserialize_primitive_collection(ty: &String, size: &Option<u8>, bound: &CollectionBound, storage: &Storage, name: &Ident, attribute: &DatatypeAttribute, endianness: &TokenStream2) -> TokenStream2
+{
+    if *storage == Storage::Borrowed {
+        return quote_deserialize_borrowed_bytes(bound, name);
+    }
+
+    match size {
+        Some(size) => {
+            let read = quote_deserialize_element(ty, attribute, &quote! { byte_stream }, endianness);
+            let var_name = format_ident!("{}_len", attribute.name);
+            let size = *size as usize;
+            let check_length = |length: TokenStream2| {
+                let message = quote_message(
+                    &format!("'{}' has a length of {{}} instead of {}", name, size),
+                    quote! { #length },
+                    &format!("'{}' does not have a length of {}", name, size),
+                );
+                quote! {
+                    if #length as usize != #size {
+                        return Err(#IO::Error::new(#IO::ErrorKind::InvalidData, #message));
+                    }
+                }
+            };
+            // arrays always hold all of their elements, a given length must match
+            let (length, terminator) = match bound {
+                CollectionBound::Prefix(LengthUnit::Elements) => (check_length(quote! { #var_name }), quote! {}),
+                CollectionBound::Field(field, LengthUnit::Elements) => (check_length(quote! { #field }), quote! {}),
+                CollectionBound::Expr(expr, LengthUnit::Elements) => (check_length(quote! { (#expr) }), quote! {}),
+                CollectionBound::Prefix(LengthUnit::Bytes) => (quote! { let _ = #var_name; }, quote! {}),
+                CollectionBound::Terminator(terminator) => {
+                    let message = format!("'{}' is missing its terminator", name);
+                    (quote! {}, quote! {
+                        if #read? != (#terminator) {
+                            return Err(#IO::Error::new(#IO::ErrorKind::InvalidData, #message));
+                        }
+                    })
+                }
+                _ => (quote! {}, quote! {}),
+            };
+            let ty: TokenStream2 = ty.parse().unwrap();
 
-                let mut #name: Vec<#ty> = Vec::with_capacity(#var_name as usize);
-                for _ in 0..#var_name {
-                    #name.push(#ty::deserialize(byte_stream)?);
+            quote! {
+                #length
+                let mut #name: [#ty; #size] = #CORE::array::from_fn(|_| <#ty as #CORE::default::Default>::default());
+                for entry in #name.iter_mut() {
+                    *entry = #read?;
                 }
+                #terminator
+            }
+        }
+        None =>  {
+            let var_name = format_ident!("{}_len", attribute.name);
+            let read = quote_deserialize_element(ty, attribute, &quote! { byte_stream }, endianness);
+            let read_sub_stream = quote_deserialize_element(ty, attribute, &quote! { sub_stream }, endianness);
+            // containers with a fixed capacity are filled in place, their capacity limits the length
+            let bounded: Option<TokenStream2> = match storage {
+                Storage::Bounded(container) => Some(container.parse().unwrap()),
+                _ => None,
+            };
+            // datatypes are read with read_many which is a single copy for plain old data
+            let datatype = primitive_size(ty).is_none() && attribute.element_string.is_none() && !ty.contains('\'') && bounded.is_none();
+            let ty: TokenStream2 = ty.parse().unwrap();
+            let container = match &bounded {
+                Some(container) => container.clone(),
+                None => quote! { #VEC<#ty> },
+            };
+            let capacity = format!("'{}' exceeds its capacity", name);
+            let exceeds_capacity = quote! {
+                return Err(#IO::Error::new(#IO::ErrorKind::InvalidData, #capacity));
+            };
+            let push = |entries: TokenStream2, entry: TokenStream2| match &bounded {
+                Some(_) => quote! {
+                    if #entries.len() == #entries.capacity() {
+                        #exceeds_capacity
+                    }
+                    #entries.extend(Some(#entry));
+                },
+                None => quote! { #entries.push(#entry); },
+            };
+            let (length, unit) = match bound {
+                CollectionBound::Prefix(unit) => (quote! {}, unit),
+                CollectionBound::Field(field, unit) => (quote! { let #var_name = #field; }, unit),
+                CollectionBound::Expr(expr, unit) => (quote! { let #var_name = #expr; }, unit),
+                CollectionBound::Terminator(terminator) => {
+                    let push = push(quote! { #name }, quote! { entry });
+                    return quote! {
+                        let mut #name: #container = <#container>::new();
+                        loop {
+                            let entry = #read?;
+                            if entry == (#terminator) {
+                                break;
+                            }
+                            #push
+                        }
+                    }
+                }
+                CollectionBound::Eof => {
+                    let push = push(quote! { #name }, quote! { #read? });
+                    return quote! {
+                        let mut #name: #container = <#container>::new();
+                        while (byte_stream.position() as usize) < byte_stream.get_ref().len() {
+                            #push
+                        }
+                    }
+                }
+            };
+
+            match unit {
+                LengthUnit::Elements if datatype => {
+                    let read_many = quote_read_many_fallback();
+                    quote! {
+                        #length
+
+                        let #name: #VEC<#ty> = {
+                            #read_many
+                            <#ty>::read_many(byte_stream, #var_name as usize)?
+                        };
+                    }
+                },
+                // the length is checked against the capacity before reading any element
+                LengthUnit::Elements if bounded.is_some() => quote! {
+                    #length
+
+                    let mut #name: #container = <#container>::new();
+                    if #var_name as usize > #name.capacity() {
+                        #exceeds_capacity
+                    }
+                    for _ in 0..#var_name {
+                        #name.extend(Some(#read?));
+                    }
+                },
+                LengthUnit::Elements => {
+                    let remaining = quote_remaining();
+                    quote! {
+                        #length
+
+                        let mut #name: #VEC<#ty> = #VEC::with_capacity((#var_name as usize).min(#remaining));
+                        for _ in 0..#var_name {
+                            #name.push(#read?);
+                        }
+                    }
+                },
+                LengthUnit::Bytes => {
+                    let invalid_length = format!("Invalid byte length for '{}'", name);
+                    let too_short = format!("'{}' is shorter than its byte length", name);
+                    let mismatch = format!("Elements of '{}' do not match its byte length", name);
+                    let push = push(quote! { entries }, quote! { entry });
+
+                    // elements are read from a sub stream so that they cannot exceed the byte length
+                    quote! {
+                        #length
+
+                        let #name = {
+                            let length = usize::try_from(#var_name)
+                                .map_err(|_| #IO::Error::new(#IO::ErrorKind::InvalidData, #invalid_length))?;
+                            let data: &[u8] = *byte_stream.get_ref();
+                            let start = byte_stream.position() as usize;
+                            let end = start
+                                .checked_add(length)
+                                .filter(|end| *end <= data.len())
+                                .ok_or_else(|| #IO::Error::new(#IO::ErrorKind::UnexpectedEof, #too_short))?;
+
+                            let mut sub_cursor = #IO::Cursor::new(&data[start..end]);
+                            let sub_stream = &mut sub_cursor;
+                            let mut entries: #container = <#container>::new();
+                            while (sub_stream.position() as usize) < length {
+                                let position = sub_stream.position();
+                                let entry = #read_sub_stream.map_err(|error| match error.kind() {
+                                    #IO::ErrorKind::UnexpectedEof => #IO::Error::new(#IO::ErrorKind::InvalidData, #mismatch),
+                                    _ => error,
+                                })?;
+                                if sub_stream.position() == position {
+                                    return Err(#IO::Error::new(#IO::ErrorKind::InvalidData, #mismatch));
+                                }
+                                #push
+                            }
+
+                            byte_stream.set_position(end as u64);
+                            entries
+                        };
+                    }
+                },
             }
         }
     }       
 }
 
-fn quote_deserialize_primitive_collection_length(t: &IntegerType, attribute: &DatatypeAttribute, endianness: &TokenStream2) -> TokenStream2
+fn quote_deserialize_primitive_collection_length(t: &IntegerType, varint: &Option<VarintEncoding>, attribute: &DatatypeAttribute, endianness: &TokenStream2) -> TokenStream2
 {
     let var_name = format_ident!("{}_len", attribute.name);
 
-    let ty : &str = t.clone().into();
-    let read = format_ident!("read_{}", ty);
+    let read = match varint {
+        Some(encoding) => quote_deserialize_varint(encoding, t, &attribute.name),
+        None => quote_deserialize_integer(t, endianness),
+    };
 
-    match t{
-        IntegerType::U8 | IntegerType::I8 => quote! { let #var_name = byte_stream.#read()?; },
-        _ => quote! { let #var_name = byte_stream.#read::<byteorder::#endianness>()?; }
-    }     
+    quote! { let #var_name = #read; }
 }
-pub(crate) fn produce_deserialize_impl(
-    name: &Ident, attrs: &Vec<DatatypeAttribute>,
-) -> TokenStream2 {
+fn quote_deserialize_magic(magic: &Vec<u8>, name: &Ident) -> TokenStream2
+{
+    let size = magic.len();
+    let message = quote_message(
+        &format!("Invalid magic for '{}': expected {{:02X?}}, got {{:02X?}}", name),
+        quote! { expected, actual },
+        &format!("Invalid magic for '{}'", name),
+    );
 
-    let attribute_names = attrs
-    .iter()
-    .filter(|attribute| attribute.reserved == false)
-    .filter(|attribute| match attribute.ty {
+    quote! {
+        {
+            let expected: [u8; #size] = [#(#magic),*];
+            let mut actual = [0u8; #size];
+            #IO::Read::read_exact(byte_stream, &mut actual)?;
+            if actual != expected {
+                return Err(#IO::Error::new(#IO::ErrorKind::InvalidData, #message));
+            }
+        }
+    }
+}
+
+fn quote_deserialize_checksum(t: &IntegerType, algorithm: &ChecksumAlgorithm, range: Option<(usize, usize)>, name: &Ident, endianness: &TokenStream2) -> TokenStream2
+{
+    // an invalid range is already reported by the validation
+    let (start, end) = match range {
+        Some((start, end)) => (offset_ident(start), offset_ident(end)),
+        None => return quote! {},
+    };
+    let ty: Ident = t.into();
+    let read = quote_deserialize_integer(t, endianness);
+    let checksum = quote_checksum(algorithm);
+    let message = quote_message(
+        &format!("Checksum mismatch for '{}': calculated {{:#X}}, got {{:#X}}", name),
+        quote! { checksum, #name },
+        &format!("Checksum mismatch for '{}'", name),
+    );
+
+    quote! {
+        let #name = #read;
+        let checksum = {
+            let data: &[u8] = &byte_stream.get_ref()[#start..#end];
+            #checksum
+        } as #ty;
+        if #name != checksum {
+            return Err(#IO::Error::new(#IO::ErrorKind::InvalidData, #message));
+        }
+    }
+}
+
+// skips the padding, its content is not checked
+fn quote_deserialize_padding(padding: &Padding, name: &Ident, align_to_stream: bool) -> TokenStream2
+{
+    let amount = match (padding, align_to_stream) {
+        (Padding::Fixed(amount), _) => {
+            let amount = *amount as u64;
+            quote! { #amount }
+        }
+        (Padding::Align(align), true) => quote_alignment(*align, &quote! { byte_stream.position() }),
+        (Padding::Align(align), false) => quote_alignment(*align, &quote! { byte_stream.position() - record_start }),
+    };
+    let message = format!("Missing padding of '{}'", name);
+
+    quote! {
+        {
+            let position = byte_stream.position() + #amount;
+            if position > byte_stream.get_ref().len() as u64 {
+                return Err(#IO::Error::new(#IO::ErrorKind::UnexpectedEof, #message));
+            }
+            byte_stream.set_position(position);
+        }
+    }
+}
+
+// replaces `self` within a calculation by `replacement`
+fn replace_self(tokens: TokenStream2, replacement: &Ident) -> TokenStream2
+{
+    tokens
+    .into_iter()
+    .map(|token| match token {
+        TokenTree::Ident(ident) if ident == "self" => TokenTree::Ident(replacement.clone()),
+        TokenTree::Group(group) => {
+            let mut replaced = Group::new(group.delimiter(), replace_self(group.stream(), replacement));
+            replaced.set_span(group.span());
+            TokenTree::Group(replaced)
+        }
+        token => token,
+    })
+    .collect()
+}
+
+// recalculates the value of a field on the deserialized datatype `result` and compares it to the
+// deserialized value
+fn quote_verify_calc(attribute: &DatatypeAttribute) -> TokenStream2
+{
+    let name = &attribute.name;
+    let expr = match &attribute.calc {
+        Some(calc) => {
+            let expr = &calc.expr;
+            replace_self(quote! { #expr }, &format_ident!("result"))
+        }
+        None => return quote! {},
+    };
+    let ty = match &attribute.ty {
+        DatatypeAttributeType::PrimitiveInteger(t) | DatatypeAttributeType::VarInt(t, _) | DatatypeAttributeType::Decimal(t, _) => {
+            let ty: Ident = t.into();
+            quote! { #ty }
+        }
+        DatatypeAttributeType::PrimitiveFloat(t) => {
+            let ty: &str = t.clone().into();
+            ty.parse().unwrap()
+        }
+        _ => return quote! {},
+    };
+    let message = quote_message(
+        &format!("'{}' does not match its calculated value: expected {{:?}}, got {{:?}}", name),
+        quote! { expected, #name },
+        &format!("'{}' does not match its calculated value", name),
+    );
+
+    quote! {
+        {
+            let expected: #ty = #expr;
+            if #name != expected {
+                return Err(#IO::Error::new(#IO::ErrorKind::InvalidData, #message));
+            }
+        }
+    }
+}
+
+// addressed fields are read after all other fields by seeking to their offset, afterwards the
+// stream is placed behind the furthest field that has been read
+fn quote_deserialize_addressed(attrs: &[DatatypeAttribute], index: usize, address: &Address, deserialize: &TokenStream2) -> TokenStream2
+{
+    let previous = index.checked_sub(1).map(|previous| &attrs[previous]);
+    let first_of_field = previous.is_none_or(|previous| previous.name != attrs[index].name || previous.address.is_none());
+    let first = previous.is_none_or(|previous| previous.address.is_none());
+    let last = attrs.get(index + 1).is_none_or(|next| next.address.is_none());
+
+    let offset = &address.offset;
+    let message = format!("Invalid offset '{}' of '{}'", offset, attrs[index].name);
+    let position = match address.base {
+        OffsetBase::Start => quote! {
+            u64::try_from(#offset).ok().and_then(|offset| record_start.checked_add(offset))
+        },
+        OffsetBase::Stream => quote! { u64::try_from(#offset).ok() },
+    };
+
+    let record_end = match first {
+        true => quote! { let mut record_end = byte_stream.position(); },
+        false => quote! {},
+    };
+    // offsets come from the input, negative ones and ones beyond u64 are rejected instead of wrapping
+    let seek = match first_of_field {
+        true => quote! {
+            let position = #position.ok_or_else(|| #IO::Error::new(#IO::ErrorKind::InvalidData, #message))?;
+            byte_stream.set_position(position);
+        },
+        false => quote! {},
+    };
+    let restore = match last {
+        true => quote! { byte_stream.set_position(record_end); },
+        false => quote! {},
+    };
+
+    quote! {
+        #record_end
+        #seek
+        #deserialize
+        record_end = record_end.max(byte_stream.position());
+        #restore
+    }
+}
+
+// attribute holding the value of a field of the generated struct
+pub(crate) fn is_field_value(attribute: &DatatypeAttribute) -> bool
+{
+    if attribute.reserved || attribute.calc.as_ref().is_some_and(|calc| calc.omit) {
+        return false;
+    }
+
+    match attribute.ty {
         DatatypeAttributeType::PrimitiveInteger(_) |
+        DatatypeAttributeType::VarInt(_, _) |
         DatatypeAttributeType::PrimitiveFloat(_) |
+        DatatypeAttributeType::Scaled(_, _) |
+        DatatypeAttributeType::EncodedFloat(_, _) |
+        DatatypeAttributeType::Decimal(_, _) |
         DatatypeAttributeType::Struct(_) |
-        DatatypeAttributeType::String |
-        DatatypeAttributeType::Collection(_, _) => true,
-        DatatypeAttributeType::CollectionLength(_) => false,
-    })
-    .map(|attribute| {
-        let name = format_ident!("{}", attribute.name);
-        quote! { #name }
-    });
+        DatatypeAttributeType::String(_, _, _) |
+        DatatypeAttributeType::Char(_) |
+        DatatypeAttributeType::Collection(_, _, _, _) => true,
+        DatatypeAttributeType::CollectionLength(_, _, _) |
+        DatatypeAttributeType::Magic(_) |
+        DatatypeAttributeType::Checksum(_, _, _) |
+        DatatypeAttributeType::Padding(_) |
+        DatatypeAttributeType::Offset(_) => false,
+    }
+}
 
-    let deserialize_impl = attrs
+// reads each attribute into a variable of the same name, relies on `byte_stream` and `record_start`
+pub(crate) fn quote_deserialize_attributes(attrs: &[DatatypeAttribute], align_to_stream: bool) -> Vec<TokenStream2>
+{
+    // offsets of the start and end of checksum ranges
+    let offsets: Vec<usize> = checksum_ranges(attrs)
     .iter()
-    .map(|attribute| {
+    .flat_map(|(_, start, end)| [*start, *end])
+    .collect();
+
+    attrs
+    .iter()
+    .enumerate()
+    .map(|(index, attribute)| {
        
         let endianness: &str = (&attribute.endianness).into();
         let endianness: TokenStream2 = endianness.parse().unwrap();
-        match &attribute.ty {
-            DatatypeAttributeType::PrimitiveInteger(t) => quote_deserialize_primitive_integer(t, &attribute.name, &endianness),
-            DatatypeAttributeType::PrimitiveFloat(t) => quote_deserialize_primitive_float(t, &attribute.name, &endianness),
+        // omitted calculated fields are only used if they are verified or referenced by another field
+        let unused = match &attribute.calc {
+            Some(calc) if calc.omit && !calc.verify => quote! { #[allow(unused_variables)] },
+            _ => quote! {},
+        };
+        let name = &attribute.name;
+        let deserialize = match &attribute.ty {
+            DatatypeAttributeType::PrimitiveInteger(t) => {
+                let read = quote_deserialize_primitive_integer(t, name, &endianness);
+                quote! { #unused #read }
+            }
+            DatatypeAttributeType::VarInt(t, encoding) => {
+                let read = quote_deserialize_varint(encoding, t, name);
+                quote! { #unused let #name = #read; }
+            },
+            DatatypeAttributeType::PrimitiveFloat(t) => {
+                let read = quote_deserialize_primitive_float(t, name, &endianness);
+                quote! { #unused #read }
+            }
+            DatatypeAttributeType::Scaled(t, scaling) => {
+                let read = quote_deserialize_scaled(t, scaling, &endianness);
+                quote! { #unused let #name = #read; }
+            }
+            DatatypeAttributeType::EncodedFloat(t, encoding) => {
+                let read = quote_deserialize_encoded_float(t, encoding, &endianness);
+                quote! { #unused let #name = #read; }
+            }
+            DatatypeAttributeType::Decimal(t, format) => {
+                let read = quote_deserialize_decimal(t, format, name, &endianness);
+                quote! { #unused let #name = #read; }
+            }
             DatatypeAttributeType::Struct(ty) => quote_deserialize_primitive_struct(ty, &attribute.name),
-            DatatypeAttributeType::String => quote! {},
-            DatatypeAttributeType::Collection(ty, size) => quote_deserialize_primitive_collection(ty, size, &attribute.name, attribute),
-            DatatypeAttributeType::CollectionLength(t) => quote_deserialize_primitive_collection_length(t, attribute, &endianness),
+            DatatypeAttributeType::String(format, encoding, storage) => quote_deserialize_primitive_string(format, encoding, storage, &attribute.name, &endianness),
+            DatatypeAttributeType::Char(encoding) => quote_deserialize_primitive_char(encoding, &attribute.name),
+            DatatypeAttributeType::Collection(ty, size, bound, storage) => quote_deserialize_primitive_collection(ty, size, bound, storage, &attribute.name, attribute, &endianness),
+            DatatypeAttributeType::CollectionLength(t, varint, _) => quote_deserialize_primitive_collection_length(t, varint, attribute, &endianness),
+            DatatypeAttributeType::Magic(magic) => quote_deserialize_magic(magic, &attribute.name),
+            DatatypeAttributeType::Checksum(t, algorithm, range) => {
+                quote_deserialize_checksum(t, algorithm, checksum_range(attrs, index, range), &attribute.name, &endianness)
+            }
+            DatatypeAttributeType::Padding(padding) => quote_deserialize_padding(padding, &attribute.name, align_to_stream),
+            DatatypeAttributeType::Offset(t) => {
+                let name = format_ident!("{}_offset", attribute.name);
+                let read = quote_deserialize_integer(t, &endianness);
+                quote! { let #name = #read; }
+            }
+        };
+
+        let constraints = quote_constraints(attribute, &quote! { #name }, true);
+        let deserialize = quote! {
+            #deserialize
+            #constraints
+        };
+
+        let deserialize = match offsets.contains(&index) {
+            true => {
+                let offset = offset_ident(index);
+                quote! {
+                    let #offset = byte_stream.position() as usize;
+                    #deserialize
+                }
+            }
+            false => deserialize,
+        };
+
+        match &attribute.address {
+            Some(address) => quote_deserialize_addressed(attrs, index, address, &deserialize),
+            None => deserialize,
         }
     })
-    .collect::<Vec<_>>();
+    .collect::<Vec<_>>()
+}
+
+// reads all attributes with `reads` and builds the datatype from them
+pub(crate) fn quote_deserialize_body(name: &Ident, attrs: &[DatatypeAttribute], reads: &[TokenStream2], align_to_stream: bool) -> TokenStream2
+{
+    let attribute_names = attrs
+    .iter()
+    .filter(|attribute| is_field_value(attribute))
+    .map(|attribute| {
+        let name = format_ident!("{}", attribute.name);
+        quote! { #name }
+    });
+
+    let verify_calc = attrs
+    .iter()
+    .filter(|attribute| attribute.calc.as_ref().is_some_and(|calc| calc.verify))
+    .map(quote_verify_calc);
+
+    // alignments and offsets can be relative to the start of the datatype
+    let relative_alignment = last_alignment(attrs).is_some() && !align_to_stream;
+    let relative_offset = attrs
+    .iter()
+    .any(|x| matches!(&x.address, Some(address) if address.base == OffsetBase::Start));
+    let record_start = match relative_alignment || relative_offset {
+        true => quote! { let record_start = byte_stream.position(); },
+        false => quote! {},
+    };
 
     quote! {
-        impl crate::datatypes::Deserialize for #name {
-            fn deserialize(byte_stream: &mut std::io::Cursor<&[u8]>) -> std::io::Result<Self> where Self: Sized {
-                use byteorder::{BigEndian, ReadBytesExt};
+        use byteorder::BigEndian;
+        use #BYTES_EXT::ReadBytesExt;
+
+        #record_start
+        #(#reads)*
 
-                #(#deserialize_impl)*
-                
-                Ok(#name {
-                    #(#attribute_names),*                    
-                })
+        let result = #name {
+            #(#attribute_names),*
+        };
+        #(#verify_calc)*
+
+        Ok(result)
+    }
+}
+
+pub(crate) fn produce_deserialize_impl(
+    name: &Ident, generics: &Generics, lifetime: Option<&Lifetime>, attrs: &[DatatypeAttribute], align_to_stream: bool,
+) -> TokenStream2 {
+    let reads = quote_deserialize_attributes(attrs, align_to_stream);
+    let body = quote_deserialize_body(name, attrs, &reads, align_to_stream);
+
+    // datatypes borrowing from the input cannot implement Deserialize which accepts input of any lifetime
+    let lifetime = match lifetime {
+        Some(lifetime) => lifetime,
+        None => return quote! {
+            impl crate::datatypes::Deserialize for #name {
+                fn deserialize(byte_stream: &mut #IO::Cursor<&[u8]>) -> #IO::Result<Self> where Self: Sized {
+                    #body
+                }
+            }
+        },
+    };
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Deserializes the datatype borrowing strings and byte slices from the input
+            pub fn read_from(byte_stream: &mut #IO::Cursor<&#lifetime [u8]>) -> #IO::Result<Self> {
+                #body
             }
         }
     }
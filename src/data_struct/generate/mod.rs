@@ -1,28 +1,240 @@
-use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use proc_macro_error::emit_error;
+use syn::{GenericParam, Generics, Lifetime};
 
 use crate::{
+    generate::{produce_async_impl, produce_bytes_impl, produce_fixed_decode_impl},
+    types::checksum::ChecksumAlgorithm,
     data_struct::{
-        generate::{deserialize::produce_deserialize_impl, serialize::produce_serialize_impl},
+        generate::{codec::produce_codec_impl, decode::produce_decode_impl, deserialize::produce_deserialize_impl, serialize::produce_serialize_impl, size::produce_fixed_size_impl, validate::produce_validate_impl, view::produce_view_impl, pod::{check_pod, produce_pod_impl}},
         parse::DataField,
+        structs::{DataStructArg, DataStructArgs},
+        checksum_range,
     },
-    structs::DatatypeAttribute,
+    structs::{CollectionBound, DatatypeAttribute, DatatypeAttributeType, Padding},
 };
 
 use super::parse::ItemStruct;
 
+pub(crate) mod codec;
+pub(crate) mod decode;
 pub(crate) mod deserialize;
+pub(crate) mod pod;
 pub(crate) mod serialize;
+pub(crate) mod size;
+pub(crate) mod validate;
+pub(crate) mod view;
+
+// ranges of all checksums as (index of the checksum, start, exclusive end)
+pub(crate) fn checksum_ranges(attrs: &[DatatypeAttribute]) -> Vec<(usize, usize, usize)> {
+    attrs
+        .iter()
+        .enumerate()
+        .filter_map(|(index, attribute)| match &attribute.ty {
+            DatatypeAttributeType::Checksum(_, _, range) => {
+                checksum_range(attrs, index, range).map(|(start, end)| (index, start, end))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+// index of the last alignment, the offset within the datatype only needs to be tracked up to it
+pub(crate) fn last_alignment(attrs: &[DatatypeAttribute]) -> Option<usize> {
+    attrs
+        .iter()
+        .rposition(|x| matches!(x.ty, DatatypeAttributeType::Padding(Padding::Align(_))))
+}
+
+// number of zero bytes needed to align `offset` (an u64 expression)
+pub(crate) fn quote_alignment(align: u32, offset: &TokenStream2) -> TokenStream2 {
+    let align = align as u64;
+    quote! { ((#align - (#offset) % #align) % #align) }
+}
+
+// 2^exponent (an i32 expression within -1086..=1023) built from its bits as float math like powi
+// is only available with std, exponents below the normal range are scaled in two steps
+pub(crate) fn quote_pow2(exponent: &TokenStream2) -> TokenStream2 {
+    quote! {
+        {
+            let exponent: i32 = #exponent;
+            match exponent >= -1022 {
+                true => f64::from_bits(((exponent + 1023) as u64) << 52),
+                false => f64::from_bits(((exponent + 1087) as u64) << 52) * f64::from_bits(((1023 - 64) as u64) << 52),
+            }
+        }
+    }
+}
+
+// rounds the f64 expression half away from zero like f64::round which is only available with std
+pub(crate) fn quote_round(value: &TokenStream2) -> TokenStream2 {
+    quote! {
+        {
+            let value: f64 = #value;
+            let magnitude = if value < 0.0 { -value } else { value };
+            match magnitude < 4503599627370496.0 {
+                true => {
+                    let truncated = magnitude as u64 as f64;
+                    let rounded = if magnitude - truncated >= 0.5 { truncated + 1.0 } else { truncated };
+                    if value < 0.0 { -rounded } else { rounded }
+                }
+                // already integral, infinite or NaN
+                false => value,
+            }
+        }
+    }
+}
+
+// variable holding the offset in bytes of the attribute at `index`, only defined for the start and
+// end of checksum ranges
+pub(crate) fn offset_ident(index: usize) -> Ident {
+    format_ident!("offset_{}", index)
+}
+
+// calculates the checksum of the byte slice `data` as u64
+pub(crate) fn quote_checksum(algorithm: &ChecksumAlgorithm) -> TokenStream2 {
+    let crc16 = |init: u16| quote! {
+        {
+            let mut crc: u16 = #init;
+            for byte in data {
+                crc ^= (*byte as u16) << 8;
+                for _ in 0..8 {
+                    crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+                }
+            }
+            crc as u64
+        }
+    };
+
+    match algorithm {
+        ChecksumAlgorithm::Sum8 => quote! {
+            data.iter().fold(0u8, |acc, byte| acc.wrapping_add(*byte)) as u64
+        },
+        ChecksumAlgorithm::Xor8 => quote! {
+            data.iter().fold(0u8, |acc, byte| acc ^ *byte) as u64
+        },
+        ChecksumAlgorithm::Crc16Ccitt => crc16(0xFFFF),
+        ChecksumAlgorithm::Crc16Xmodem => crc16(0),
+        ChecksumAlgorithm::Crc32 => quote! {
+            {
+                let mut crc: u32 = 0xFFFFFFFF;
+                for byte in data {
+                    crc ^= *byte as u32;
+                    for _ in 0..8 {
+                        crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB88320 } else { crc >> 1 };
+                    }
+                }
+                (!crc) as u64
+            }
+        },
+        ChecksumAlgorithm::Custom(path) => quote! {
+            <#path as crate::datatypes::Checksum>::checksum(data)
+        },
+    }
+}
+
+// lifetime of a datatype borrowing from the input, only a single lifetime parameter is supported
+fn borrowed_lifetime(generics: &Generics) -> Option<&Lifetime> {
+    let mut lifetimes = vec![];
+    for param in &generics.params {
+        match param {
+            GenericParam::Lifetime(param) => lifetimes.push(&param.lifetime),
+            _ => emit_error!(param, "Only a lifetime parameter is supported on datatypes"),
+        }
+    }
+
+    if lifetimes.len() > 1 {
+        emit_error!(generics.params, "Datatypes can only borrow from the input with a single lifetime parameter");
+    }
+
+    lifetimes.first().copied()
+}
 
 pub(crate) fn produce(
     datatype_struct: &ItemStruct,
     attrs: &Vec<DatatypeAttribute>,
+    args: &DataStructArgs,
 ) -> TokenStream2 {
     let name = &datatype_struct.ident;
 
-    let serialize_impl = produce_serialize_impl(&name, attrs);
-    let deserialize_impl = produce_deserialize_impl(&name, attrs);
+    let align_to_stream = args
+        .0
+        .iter()
+        .rev()
+        .find_map(|x| match x {
+            DataStructArg::AlignToStream(x) => Some(*x),
+            _ => None,
+        })
+        .unwrap_or(false);
+
+    let generics = &datatype_struct.generics;
+    let lifetime = borrowed_lifetime(generics);
+
+    // without `serialize` or `deserialize` both directions are generated
+    let directions = args.0.iter().any(|x| matches!(x, DataStructArg::Serialize | DataStructArg::Deserialize));
+    let serialize = !directions || args.0.iter().any(|x| matches!(x, DataStructArg::Serialize));
+    let deserialize = !directions || args.0.iter().any(|x| matches!(x, DataStructArg::Deserialize));
+
+    let pod = args.0.iter().any(|x| matches!(x, DataStructArg::Pod));
+    let codec = args.0.iter().any(|x| matches!(x, DataStructArg::Codec { .. }));
+    if (pod || codec) && !(serialize && deserialize) {
+        emit_error!(name, "Datatypes with 'pod' or 'codec' are always generated with serialize and deserialize");
+    }
+    if pod && !generics.params.is_empty() {
+        emit_error!(generics.params, "Datatypes with 'pod' cannot have generic parameters");
+    }
+    // invalid datatypes fall back to the regular implementation after reporting why
+    let pod = pod && generics.params.is_empty() && check_pod(datatype_struct, attrs);
+
+    let fixed_size_impl = produce_fixed_size_impl(name, generics, attrs, args, align_to_stream);
+    let (serialize_impl, deserialize_impl, validate_impl) = match pod {
+        true => (produce_pod_impl(name, attrs), quote! {}, quote! {}),
+        false => (
+            match serialize {
+                true => produce_serialize_impl(name, generics, attrs, align_to_stream, fixed_size_impl.is_some()),
+                false => quote! {},
+            },
+            match deserialize {
+                true => produce_deserialize_impl(name, generics, lifetime, attrs, align_to_stream),
+                false => quote! {},
+            },
+            produce_validate_impl(name, generics, attrs),
+        ),
+    };
+    let bytes_impl = produce_bytes_impl(name, generics, lifetime, serialize, deserialize);
+    let decode_impl = match (deserialize, fixed_size_impl.is_some()) {
+        (false, _) => quote! {},
+        (true, true) => produce_fixed_decode_impl(name, generics, lifetime),
+        (true, false) => produce_decode_impl(name, generics, lifetime, attrs, align_to_stream),
+    };
+    let async_impl = match cfg!(feature = "async") {
+        true => {
+            let until_eof = attrs
+                .iter()
+                .any(|x| matches!(x.ty, DatatypeAttributeType::Collection(_, _, CollectionBound::Eof, _)));
+            produce_async_impl(name, generics, lifetime, fixed_size_impl.is_some(), until_eof, serialize, deserialize)
+        }
+        false => quote! {},
+    };
+    let repr = match pod {
+        true => quote! { #[repr(C, packed)] },
+        false => quote! {},
+    };
+    let codec_impl = args
+        .0
+        .iter()
+        .find_map(|x| match x {
+            DataStructArg::Codec { length, max_frame_size } => Some(produce_codec_impl(datatype_struct, lifetime, attrs, length, *max_frame_size)),
+            _ => None,
+        })
+        .unwrap_or_default();
+    let view_impl = match args.0.iter().any(|x| matches!(x, DataStructArg::View)) {
+        true => produce_view_impl(datatype_struct, lifetime, attrs, align_to_stream),
+        false => quote! {},
+    };
 
     let visibility = &datatype_struct.visibility;
+    let where_clause = &generics.where_clause;
     let attributes = &datatype_struct.attrs;
 
     let filtered: Vec<&DataField> = datatype_struct
@@ -32,7 +244,10 @@ pub(crate) fn produce(
             x.attrs
                 .iter()
                 .filter(|x| match x {
-                    crate::data_struct::structs::DataFieldArg::Reserved => true,
+                    crate::data_struct::structs::DataFieldArg::Reserved |
+                    crate::data_struct::structs::DataFieldArg::Magic(_) |
+                    crate::data_struct::structs::DataFieldArg::Checksum(_) |
+                    crate::data_struct::structs::DataFieldArg::Omit => true,
                     _ => false,
                 })
                 .count()
@@ -42,7 +257,8 @@ pub(crate) fn produce(
 
     let ast = quote! {
         #(#attributes),*
-        #visibility struct #name {
+        #repr
+        #visibility struct #name #generics #where_clause {
             #(#filtered),*
         }
     };
@@ -54,5 +270,19 @@ pub(crate) fn produce(
         #serialize_impl
 
         #deserialize_impl
+
+        #bytes_impl
+
+        #decode_impl
+
+        #async_impl
+
+        #validate_impl
+
+        #fixed_size_impl
+
+        #view_impl
+
+        #codec_impl
     }
 }
@@ -0,0 +1,238 @@
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use proc_macro_error::emit_error;
+
+use crate::{
+    data_struct::{
+        generate::{serialize::primitive_size, validate::quote_constraints},
+        parse::ItemStruct,
+    },
+    generate::{quote_message, CORE, IO, VEC, WRITER},
+    structs::{CollectionBound, DatatypeAttribute, DatatypeAttributeType, DatatypeEndianness},
+    types::{float::FloatType, int::IntegerType},
+};
+
+// checks that the datatype consists of fixed-width integers, floats and arrays of them in the order
+// of the struct so that its memory layout with repr(C, packed) matches the wire format
+pub(crate) fn check_pod(datatype_struct: &ItemStruct, attrs: &[DatatypeAttribute]) -> bool {
+    let mut valid = true;
+
+    for attribute in attrs {
+        let name = &attribute.name;
+        let supported = match &attribute.ty {
+            DatatypeAttributeType::PrimitiveInteger(_) | DatatypeAttributeType::PrimitiveFloat(_) => true,
+            DatatypeAttributeType::Collection(ty, Some(_), bound, _) => {
+                // a length prefix is reported with its own attribute
+                primitive_size(ty).is_some() && !matches!(bound, CollectionBound::Terminator(_))
+            }
+            DatatypeAttributeType::CollectionLength(_, _, _) => {
+                emit_error!(name.span(), "The length of '{}' is written which is not supported by 'pod', use 'count' with the length of the array", name);
+                valid = false;
+                continue;
+            }
+            DatatypeAttributeType::Magic(_) | DatatypeAttributeType::Padding(_) | DatatypeAttributeType::Checksum(_, _, _) => {
+                emit_error!(name.span(), "Magic, padding and checksums of '{}' are not part of the struct which is required by 'pod'", name);
+                valid = false;
+                continue;
+            }
+            _ => false,
+        };
+
+        if !supported || attribute.reserved || attribute.calc.is_some() || attribute.address.is_some() {
+            emit_error!(name.span(), "'{}' is not a fixed-width integer, float or array of them which is required by 'pod'", name);
+            valid = false;
+        }
+    }
+
+    let declared = datatype_struct.fields.iter().map(|field| &field.name);
+    if valid && !attrs.iter().map(|attribute| &attribute.name).eq(declared) {
+        emit_error!(datatype_struct.ident.span(), "'pod' requires the fields to be (de-)serialized in the order of the struct");
+        valid = false;
+    }
+
+    valid
+}
+
+// converts a value between host and wire byte order, the conversion is its own inverse. None for
+// single bytes which have no byte order
+fn quote_swap(ty: &str, endianness: &DatatypeEndianness, value: &TokenStream2) -> Option<TokenStream2> {
+    let from = match endianness {
+        DatatypeEndianness::BigEndian => quote! { from_be },
+        DatatypeEndianness::LittleEndian => quote! { from_le },
+    };
+
+    if let Ok(t) = IntegerType::try_from(ty) {
+        let ty: Ident = (&t).into();
+        return match t.size() {
+            1 => None,
+            _ => Some(quote! { #ty::#from(#value) }),
+        };
+    }
+
+    let (float, bits) = match FloatType::try_from(ty) {
+        Ok(FloatType::F32) => (quote! { f32 }, quote! { u32 }),
+        _ => (quote! { f64 }, quote! { u64 }),
+    };
+    Some(quote! { #float::from_bits(#bits::#from(#value.to_bits())) })
+}
+
+// swaps all fields between host and wire byte order, no-op if they match
+fn quote_swap_fields(attrs: &[DatatypeAttribute]) -> TokenStream2 {
+    let swaps = attrs.iter().filter_map(|attribute| {
+        let name = &attribute.name;
+        let ty: &str = match &attribute.ty {
+            DatatypeAttributeType::PrimitiveInteger(t) => t.into(),
+            DatatypeAttributeType::PrimitiveFloat(t) => t.clone().into(),
+            DatatypeAttributeType::Collection(ty, _, _, _) => ty,
+            _ => return None,
+        };
+
+        match &attribute.ty {
+            // fields of packed structs cannot be borrowed
+            DatatypeAttributeType::Collection(_, _, _, _) => {
+                let swap = quote_swap(ty, &attribute.endianness, &quote! { *entry })?;
+                Some(quote! {
+                    let mut entries = self.#name;
+                    for entry in entries.iter_mut() {
+                        *entry = #swap;
+                    }
+                    self.#name = entries;
+                })
+            }
+            _ => {
+                let swap = quote_swap(ty, &attribute.endianness, &quote! { self.#name })?;
+                Some(quote! { self.#name = #swap; })
+            }
+        }
+    });
+
+    quote! { #(#swaps)* }
+}
+
+// checks constraints and array lengths of a value in host byte order with all fields bound to
+// variables of the same name
+fn quote_checks(attrs: &[DatatypeAttribute]) -> TokenStream2 {
+    let checks = attrs.iter().map(|attribute| {
+        let name = &attribute.name;
+        let constraints = quote_constraints(attribute, &quote! { #name }, true);
+        let length = match &attribute.ty {
+            DatatypeAttributeType::Collection(_, Some(size), CollectionBound::Field(field, _), _) => Some((quote! { #field }, *size as usize)),
+            DatatypeAttributeType::Collection(_, Some(size), CollectionBound::Expr(expr, _), _) => Some((quote! { (#expr) }, *size as usize)),
+            _ => None,
+        };
+        let length = match length {
+            Some((length, size)) => {
+                let message = quote_message(
+                    &format!("'{}' has a length of {{}} instead of {}", name, size),
+                    quote! { #length },
+                    &format!("'{}' does not have a length of {}", name, size),
+                );
+                quote! {
+                    if #length as usize != #size {
+                        return Err(#IO::Error::new(#IO::ErrorKind::InvalidData, #message));
+                    }
+                }
+            }
+            None => quote! {},
+        };
+
+        quote! {
+            #length
+            #constraints
+        }
+    });
+
+    quote! { #(#checks)* }
+}
+
+// bulk (de-)serialization copying the memory of the repr(C, packed) struct, only the byte order of
+// the fields is converted
+pub(crate) fn produce_pod_impl(name: &Ident, attrs: &[DatatypeAttribute]) -> TokenStream2 {
+    let fields: Vec<&Ident> = attrs.iter().map(|attribute| &attribute.name).collect();
+    let swaps = quote_swap_fields(attrs);
+    let checks = quote_checks(attrs);
+    let layout = format!("The memory layout of '{}' does not match its serialized size", name);
+    let too_short = format!("The input is too short for the requested amount of '{}'", name);
+
+    quote! {
+        const _: () = assert!(#CORE::mem::size_of::<#name>() == #name::SIZE, #layout);
+
+        impl #name {
+            fn swap_byte_order(&mut self) {
+                #swaps
+            }
+
+            /// Checks the constraints of all fields, deserialization performs the same checks
+            pub fn validate(&self) -> #IO::Result<()> {
+                // fields of packed structs cannot be borrowed and are therefore copied
+                #(#[allow(unused_variables)] let #fields = self.#fields;)*
+                #checks
+
+                Ok(())
+            }
+
+            /// Serializes the datatype into any seekable writer
+            pub fn write_to<W: #IO::Write + #IO::Seek>(&self, writer: &mut W) -> #IO::Result<()> {
+                // all fields are plain integers and floats without any drop glue
+                let mut value: #name = unsafe { #CORE::ptr::read(self) };
+                value.swap_byte_order();
+                let bytes = unsafe { #CORE::slice::from_raw_parts(&value as *const #name as *const u8, #name::SIZE) };
+
+                #IO::Write::write_all(writer, bytes)
+            }
+
+            /// Reads `count` consecutive datatypes with a single copy
+            pub fn read_many(byte_stream: &mut #IO::Cursor<&[u8]>, count: usize) -> #IO::Result<#VEC<Self>> {
+                let data: &[u8] = *byte_stream.get_ref();
+                let start = byte_stream.position() as usize;
+                let end = count
+                    .checked_mul(#name::SIZE)
+                    .and_then(|length| start.checked_add(length))
+                    .filter(|end| *end <= data.len())
+                    .ok_or_else(|| #IO::Error::new(#IO::ErrorKind::UnexpectedEof, #too_short))?;
+
+                let mut entries: #VEC<#name> = #VEC::with_capacity(count);
+                // every bit pattern is a valid value of the packed struct of integers and floats
+                unsafe {
+                    #CORE::ptr::copy_nonoverlapping(data[start..end].as_ptr(), entries.as_mut_ptr() as *mut u8, end - start);
+                    entries.set_len(count);
+                }
+                for entry in entries.iter_mut() {
+                    entry.swap_byte_order();
+                    entry.validate()?;
+                }
+
+                byte_stream.set_position(end as u64);
+                Ok(entries)
+            }
+        }
+
+        impl crate::datatypes::WriteTo for #name {
+            fn write_to<W: #IO::Write + #IO::Seek>(&self, writer: &mut W) -> #IO::Result<()> {
+                Self::write_to(self, writer)
+            }
+        }
+
+        impl crate::datatypes::Serialize for #name {
+            fn serialize(&self, writer: &mut #WRITER) -> #IO::Result<()> {
+                self.write_to(writer)
+            }
+
+            fn size(&self) -> u32 {
+                #name::SIZE as u32
+            }
+        }
+
+        impl crate::datatypes::Deserialize for #name {
+            fn deserialize(byte_stream: &mut #IO::Cursor<&[u8]>) -> #IO::Result<Self> where Self: Sized {
+                let mut bytes = [0u8; #name::SIZE];
+                #IO::Read::read_exact(byte_stream, &mut bytes)?;
+                // every bit pattern is a valid value of the packed struct of integers and floats
+                let mut value: #name = unsafe { #CORE::ptr::read_unaligned(bytes.as_ptr() as *const #name) };
+                value.swap_byte_order();
+                value.validate()?;
+
+                Ok(value)
+            }
+        }
+    }
+}
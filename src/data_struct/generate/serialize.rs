@@ -1,9 +1,21 @@
-use proc_macro2::{Ident, TokenStream as TokenStream2};
+use proc_macro2::{Ident, Literal, TokenStream as TokenStream2};
+use syn::{Expr, Generics};
 
 
 use crate::{
-    structs::{DatatypeAttribute, DatatypeAttributeType},
-    types::{float::FloatType, int::IntegerType},
+    data_struct::checksum_range,
+    data_struct::generate::{checksum_ranges, last_alignment, offset_ident, quote_alignment, quote_checksum, quote_round},
+    generate::{quote_message, BYTES_EXT, IO, VEC, WRITER},
+    structs::{CollectionBound, DatatypeAttribute, DatatypeAttributeType, LengthUnit, OffsetBase, Padding},
+    types::{
+        checksum::ChecksumAlgorithm,
+        decimal::DecimalFormat,
+        float::{FloatEncoding, FloatType},
+        int::IntegerType,
+        scaled::Scaling,
+        string::{Charset, StringFormat, TextEncoding, WINDOWS_1252},
+        varint::VarintEncoding,
+    },
 };
 
 fn quote_serialize_size_primitive_integer(t: &IntegerType) -> TokenStream2 {
@@ -16,20 +28,76 @@ fn quote_serialize_size_primitive_float(t: &FloatType) -> TokenStream2 {
 
     quote! {#size}
 }
-fn quote_serialize_size_primitive_string(attribute_name: &Ident) -> TokenStream2 {
-    quote! {self.#attribute_name.len() + 1}
+// number of bytes the string `value` occupies once encoded
+fn quote_encoded_len(encoding: &TextEncoding, value: &TokenStream2) -> TokenStream2 {
+    match encoding.charset {
+        Charset::Utf8 => quote! { #value.len() },
+        Charset::Ascii | Charset::Latin1 | Charset::Windows1252 => quote! { #value.chars().count() },
+        Charset::Utf16Le | Charset::Utf16Be => quote! { (#value.encode_utf16().count() * 2) },
+    }
+}
+
+// value written to the length prefix of a string with the given amount of encoded bytes
+fn quote_string_length(encoding: &TextEncoding, encoded_len: &TokenStream2) -> TokenStream2 {
+    let unit_size = encoding.charset.unit_size();
+
+    match encoding.count_units {
+        true => quote! { (#encoded_len / #unit_size) },
+        false => quote! { #encoded_len },
+    }
+}
+
+fn quote_serialize_size_primitive_string(format: &StringFormat, encoding: &TextEncoding, value: &TokenStream2) -> TokenStream2 {
+    let encoded_len = quote_encoded_len(encoding, value);
+    let unit_size = encoding.charset.unit_size();
+
+    match format {
+        StringFormat::Terminated(_) => quote! { (#encoded_len + #unit_size) as u32 },
+        StringFormat::Prefixed(t, None) => {
+            let size = t.size();
+            quote! { #size + #encoded_len as u32 }
+        }
+        StringFormat::Prefixed(t, Some(varint)) => {
+            let size = quote_serialize_size_varint(varint, t, &quote_string_length(encoding, &encoded_len));
+            quote! { #size + #encoded_len as u32 }
+        }
+        StringFormat::Fixed { width, .. } => quote! { #width as u32 },
+    }
+}
+
+fn quote_serialize_size_primitive_char(encoding: &TextEncoding, attribute_name: &Ident) -> TokenStream2 {
+    match encoding.charset {
+        Charset::Utf8 => quote! { self.#attribute_name.len_utf8() as u32 },
+        Charset::Ascii | Charset::Latin1 | Charset::Windows1252 => quote! { 1u32 },
+        Charset::Utf16Le | Charset::Utf16Be => quote! { (self.#attribute_name.len_utf16() * 2) as u32 },
+    }
+}
+
+// size of a primitive collection element, None for Datatypes
+pub(crate) fn primitive_size(embedded_ty: &str) -> Option<u32> {
+    match (IntegerType::try_from(embedded_ty), FloatType::try_from(embedded_ty)) {
+        (Ok(t), _) => Some(t.size()),
+        (_, Ok(t)) => Some(t.size()),
+        _ => None,
+    }
 }
 
 fn quote_serialize_size_primitive_collection(
     embedded_ty: &String,
     size: &Option<u8>,
     attribute_name: &Ident,
+    element_string: &Option<(StringFormat, TextEncoding)>,
 ) -> TokenStream2 {
-    match IntegerType::try_from(embedded_ty.as_str()) {
-        // for primitives like u8,u16 etc we simply need the length * bytes of the primitive
-        Ok(embbed_type) => {
-            let embedded_size = embbed_type.size();
+    if let Some((format, encoding)) = element_string {
+        let size = quote_serialize_size_primitive_string(format, encoding, &quote! { entry });
+        return quote! {
+            self.#attribute_name.iter().fold(0, |acc, entry| acc + #size)
+        };
+    }
 
+    match primitive_size(embedded_ty) {
+        // for primitives like u8,u16 etc we simply need the length * bytes of the primitive
+        Some(embedded_size) => {
             match size {
                 Some(size) => quote! {
                     (#size as u32 * #embedded_size)  as u32
@@ -42,19 +110,93 @@ fn quote_serialize_size_primitive_collection(
 
         // if we failed to convert the type to a primitive we assume a custom type that must implement a size function
         // or in other words: the embedded type is also a Datatype using the macro.
-        Err(_) => quote! {
+        None => quote! {
             self.#attribute_name.iter().fold(0, |acc, embedded_type| acc + embedded_type.size())
         },
     }
 }
+
+fn quote_serialize_size_terminator(embedded_ty: &str, terminator: &Expr) -> TokenStream2 {
+    match primitive_size(embedded_ty) {
+        Some(size) => quote! { #size },
+        None => {
+            let ty: TokenStream2 = embedded_ty.parse().unwrap();
+            quote! {
+                {
+                    let terminator: #ty = #terminator;
+                    terminator.size()
+                }
+            }
+        }
+    }
+}
+fn quote_serialize_size_varint(
+    encoding: &VarintEncoding,
+    t: &IntegerType,
+    value: &TokenStream2,
+) -> TokenStream2 {
+    match (encoding, t.is_signed()) {
+        (VarintEncoding::Leb128, true) => quote! {
+            {
+                let mut value = (#value) as i128;
+                let mut size = 1u32;
+                loop {
+                    let byte = (value & 0x7f) as u8;
+                    value >>= 7;
+                    if (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0) {
+                        break size;
+                    }
+                    size += 1;
+                }
+            }
+        },
+        _ => {
+            let value = quote_varint_unsigned_value(encoding, value);
+
+            quote! {
+                {
+                    let mut value = #value;
+                    let mut size = 1u32;
+                    while value >= 0x80 {
+                        value >>= 7;
+                        size += 1;
+                    }
+                    size
+                }
+            }
+        }
+    }
+}
+
 fn quote_serialize_size_primitive_struct(attribute_name: &Ident) -> TokenStream2 {
     quote! {self.#attribute_name.size()}
 }
 
+// length of a collection in the given unit
+fn quote_collection_length(attrs: &[DatatypeAttribute], collection: &Ident, unit: &LengthUnit) -> TokenStream2 {
+    match unit {
+        LengthUnit::Elements => quote! { self.#collection.len() },
+        LengthUnit::Bytes => {
+            let size = attrs
+                .iter()
+                .find_map(|attribute| match &attribute.ty {
+                    DatatypeAttributeType::Collection(embedded_type, size, _, _) if attribute.name == *collection => {
+                        Some(quote_serialize_size_primitive_collection(embedded_type, size, collection, &attribute.element_string))
+                    }
+                    _ => None,
+                })
+                .expect("length attribute without collection");
+
+            quote! { (#size) }
+        }
+    }
+}
+
 fn quote_serialize_primitive_integer(
     attribute: &DatatypeAttribute,
     t: &IntegerType,
     endianness: &TokenStream2,
+    length: &Option<TokenStream2>,
 ) -> TokenStream2 {
     let attribute_name = &attribute.name;
 
@@ -66,6 +208,17 @@ fn quote_serialize_primitive_integer(
         _ => quote! { writer.#write::<byteorder::#endianness> },
     };
 
+    if let (Some((collection, _)), Some(length)) = (&attribute.length_of, length) {
+        let message = format!("'{}' is too long for its length field '{}'", collection, attribute_name);
+
+        return quote! {
+            #writer_fragment(
+                #ty::try_from(#length)
+                    .map_err(|_| #IO::Error::new(#IO::ErrorKind::InvalidInput, #message))?
+            )?;
+        };
+    }
+
     match attribute.reserved {
         true => {
             let var_name = format_ident!("_{}", attribute_name);
@@ -78,51 +231,677 @@ fn quote_serialize_primitive_integer(
         false => quote! { #writer_fragment(self.#attribute_name)?; },
     }
 }
-fn quote_serialize_primitive_float(
+// value as u128 that is split into 7 bit groups for all encodings except signed LEB128
+fn quote_varint_unsigned_value(encoding: &VarintEncoding, value: &TokenStream2) -> TokenStream2 {
+    match encoding {
+        VarintEncoding::Zigzag => quote! {
+            {
+                let value = (#value) as i128;
+                ((value << 1) ^ (value >> 127)) as u128
+            }
+        },
+        _ => quote! { (#value) as u128 },
+    }
+}
+
+fn quote_serialize_varint(
+    encoding: &VarintEncoding,
+    t: &IntegerType,
+    value: &TokenStream2,
+) -> TokenStream2 {
+    match (encoding, t.is_signed()) {
+        (VarintEncoding::Leb128, true) => quote! {
+            {
+                let mut value = (#value) as i128;
+                loop {
+                    let byte = (value & 0x7f) as u8;
+                    value >>= 7;
+                    if (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0) {
+                        writer.write_u8(byte)?;
+                        break;
+                    }
+                    writer.write_u8(byte | 0x80)?;
+                }
+            }
+        },
+        (VarintEncoding::Vlq, _) => quote! {
+            {
+                let value = (#value) as u128;
+                let mut shift = 0u32;
+                while shift + 7 < 128 && value >> (shift + 7) != 0 {
+                    shift += 7;
+                }
+                loop {
+                    let byte = ((value >> shift) & 0x7f) as u8;
+                    if shift == 0 {
+                        writer.write_u8(byte)?;
+                        break;
+                    }
+                    writer.write_u8(byte | 0x80)?;
+                    shift -= 7;
+                }
+            }
+        },
+        _ => {
+            let value = quote_varint_unsigned_value(encoding, value);
+
+            quote! {
+                {
+                    let mut value = #value;
+                    loop {
+                        let byte = (value & 0x7f) as u8;
+                        value >>= 7;
+                        if value == 0 {
+                            writer.write_u8(byte)?;
+                            break;
+                        }
+                        writer.write_u8(byte | 0x80)?;
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn quote_serialize_primitive_float(
     t: &FloatType,
-    name: &Ident,
+    value: &TokenStream2,
     endianness: &TokenStream2,
 ) -> TokenStream2 {
     let ty: &str = t.clone().into();
     let write = format_ident!("write_{}", ty);
 
-    quote! { writer.#write::<byteorder::#endianness>(self.#name)?; }
+    quote! { writer.#write::<byteorder::#endianness>(#value)?; }
+}
+
+// converts the float `value` into its raw integer, values that do not fit into it are rejected
+pub(crate) fn quote_serialize_scaled(scaling: &Scaling, name: &Ident, value: &TokenStream2, endianness: &TokenStream2) -> TokenStream2 {
+    let ty: Ident = (&scaling.raw).into();
+    let scale = Literal::f64_suffixed(scaling.scale);
+    let offset = Literal::f64_suffixed(scaling.offset);
+    // the upper bound is exclusive as the maximum of 64 and 128 bit integers is not representable as f64
+    let (min, max) = match scaling.raw.is_signed() {
+        true => (quote! { #ty::MIN as f64 }, quote! { -(#ty::MIN as f64) }),
+        false => (quote! { 0.0 }, quote! { #ty::MAX as f64 + 1.0 }),
+    };
+    let message = quote_message(
+        &format!("'{}' {{:?}} does not fit into its raw type {}", name, ty),
+        quote! { #value },
+        &format!("'{}' does not fit into its raw type {}", name, ty),
+    );
+    let write = quote_serialize_integer(&scaling.raw, endianness, &quote! { raw as #ty });
+    let raw = quote_round(&quote! { (#value as f64 - #offset) / #scale });
+
+    quote! {
+        {
+            let raw = #raw;
+            if !(raw >= #min && raw < #max) {
+                return Err(#IO::Error::new(#IO::ErrorKind::InvalidInput, #message));
+            }
+            #write
+        }
+    }
 }
-fn quote_serialize_primitive_string() -> TokenStream2 {
+
+// splits the finite f64 `value` into `mantissa * 2^exponent` with an integer mantissa, `top` is the
+// exponent of the value rounded up so that it lies within [2^(top - 1), 2^top)
+fn quote_float_parts() -> TokenStream2 {
     quote! {
-        writer.write_all(&self.raw_message.as_bytes())?;
-        writer.write_u8(0)?;
+        let bits = value.to_bits();
+        let negative = bits >> 63 != 0;
+        let (mantissa, exponent) = match ((bits >> 52) & 0x7ff) as i32 {
+            0 => (bits & 0x000f_ffff_ffff_ffff, -1074),
+            biased => ((bits & 0x000f_ffff_ffff_ffff) | 0x0010_0000_0000_0000, biased - 1075),
+        };
+        let top = exponent + (64 - mantissa.leading_zeros()) as i32;
     }
 }
 
-fn quote_serialize_primitive_collection(
-    _: &String,
+// shifts `mantissa` right by `shift` bits (left if negative) and rounds to the nearest value, ties to even
+fn quote_round_mantissa(shift: &TokenStream2) -> TokenStream2 {
+    quote! {
+        {
+            let shift: i32 = #shift;
+            let mantissa = mantissa as u128;
+            if shift <= 0 {
+                (mantissa << -shift) as u64
+            } else if shift >= 64 {
+                0
+            } else {
+                let shifted = mantissa >> shift;
+                let remainder = mantissa & ((1u128 << shift) - 1);
+                let half = 1u128 << (shift - 1);
+                match remainder > half || (remainder == half && shifted & 1 == 1) {
+                    true => (shifted + 1) as u64,
+                    false => shifted as u64,
+                }
+            }
+        }
+    }
+}
+
+// rounds the float `value` to the nearest 16 bit IEEE float with the given amount of exponent and mantissa
+// bits, ties to even. The bits of the f64 are rounded directly as a detour through f32 would round twice
+fn quote_narrow_float(value: &TokenStream2, exponent_bits: u32, mantissa_bits: u32) -> TokenStream2 {
+    let bias = (1i32 << (exponent_bits - 1)) - 1;
+    let max_exponent = (1i32 << exponent_bits) - 1;
+    let infinity = (max_exponent as u16) << mantissa_bits;
+    let quiet = 1u16 << (mantissa_bits - 1);
+    let dropped = 52 - mantissa_bits;
+    let min_exponent = -(mantissa_bits as i32);
+
+    quote! {
+        {
+            let bits = (#value as f64).to_bits();
+            let sign = ((bits >> 48) & 0x8000) as u16;
+            let raw = ((bits >> 52) & 0x7ff) as i32;
+            let exponent = raw - 1023 + #bias;
+            let mantissa = bits & 0x000f_ffff_ffff_ffff;
+            if raw == 0x7ff {
+                match mantissa {
+                    0 => sign | #infinity,
+                    _ => sign | #infinity | #quiet | (mantissa >> #dropped) as u16,
+                }
+            } else if exponent >= #max_exponent {
+                sign | #infinity
+            } else if exponent < #min_exponent {
+                sign
+            } else {
+                // normal numbers drop the surplus mantissa bits, subnormal ones additionally the exponent
+                let (mantissa, shift) = match exponent <= 0 {
+                    true => (mantissa | 0x0010_0000_0000_0000, (#dropped as i32 + 1 - exponent) as u32),
+                    false => (((exponent as u64) << 52) | mantissa, #dropped),
+                };
+                let shifted = mantissa >> shift;
+                let remainder = mantissa & ((1 << shift) - 1);
+                let halfway = 1 << (shift - 1);
+                // a carry into the exponent results in the next power of two or infinity
+                match remainder > halfway || (remainder == halfway && shifted & 1 == 1) {
+                    true => sign | (shifted + 1) as u16,
+                    false => sign | shifted as u16,
+                }
+            }
+        }
+    }
+}
+
+// converts the float `value` into the given encoding, values that cannot be represented are rejected
+pub(crate) fn quote_serialize_encoded_float(encoding: &FloatEncoding, name: &Ident, value: &TokenStream2, endianness: &TokenStream2) -> TokenStream2 {
+    let encoding_name: &str = encoding.into();
+    let message = quote_message(
+        &format!("'{}' {{:?}} cannot be represented as {}", name, encoding_name),
+        quote! { value },
+        &format!("'{}' cannot be represented as {}", name, encoding_name),
+    );
+    let error = quote! {
+        return Err(#IO::Error::new(#IO::ErrorKind::InvalidInput, #message));
+    };
+    let parts = quote_float_parts();
+
+    match encoding {
+        // values that are too large become infinite like on any IEEE conversion
+        FloatEncoding::F16 | FloatEncoding::Bf16 => {
+            let half = match encoding {
+                FloatEncoding::F16 => quote_narrow_float(value, 5, 10),
+                _ => quote_narrow_float(value, 8, 7),
+            };
+            quote! {
+                {
+                    let half = #half;
+                    writer.write_u16::<byteorder::#endianness>(half)?;
+                }
+            }
+        }
+        FloatEncoding::Ibm32 | FloatEncoding::Ibm64 => {
+            let (fraction_bits, write) = match encoding {
+                FloatEncoding::Ibm32 => (24i32, quote! { writer.write_u32::<byteorder::#endianness>(bits as u32)?; }),
+                _ => (56i32, quote! { writer.write_u64::<byteorder::#endianness>(bits)?; }),
+            };
+            let round = quote_round_mantissa(&quote! { 4 * characteristic - #fraction_bits - exponent });
+            quote! {
+                {
+                    let value = #value as f64;
+                    if !value.is_finite() {
+                        #error
+                    }
+                    let bits: u64 = match value == 0.0 {
+                        true => 0,
+                        false => {
+                            #parts
+                            // the fraction lies within [1/16, 1) and is multiplied by 16^characteristic,
+                            // values that are too small use a smaller fraction
+                            let mut characteristic = (top + 3).div_euclid(4).max(-64);
+                            let mut fraction = #round;
+                            if fraction >> #fraction_bits != 0 {
+                                fraction >>= 4;
+                                characteristic += 1;
+                            }
+                            if characteristic > 63 {
+                                #error
+                            }
+                            match fraction {
+                                0 => 0,
+                                _ => ((negative as u64) << (#fraction_bits + 7)) | (((characteristic + 64) as u64) << #fraction_bits) | fraction,
+                            }
+                        }
+                    };
+                    #write
+                }
+            }
+        }
+        FloatEncoding::VaxF | FloatEncoding::VaxG => {
+            let (words, fraction_bits, exponent_bias) = match encoding {
+                FloatEncoding::VaxF => (2u32, 24i32, 128i32),
+                _ => (4u32, 53i32, 1024i32),
+            };
+            let sign_shift = words * 16 - 1;
+            let max_exponent = (1i32 << (sign_shift as i32 - fraction_bits + 1)) - 1;
+            let round = quote_round_mantissa(&quote! { top - #fraction_bits - exponent });
+            let shifts = (0..words).rev().map(|x| x * 16);
+            quote! {
+                {
+                    let value = #value as f64;
+                    if value.is_infinite() {
+                        #error
+                    }
+                    let bits: u64 = if value.is_nan() {
+                        // reserved operand
+                        1 << #sign_shift
+                    } else if value == 0.0 {
+                        0
+                    } else {
+                        #parts
+                        // 0.1fff multiplied by 2^(exponent - bias) with the leading 1 not being stored
+                        let mut biased = top + #exponent_bias;
+                        let mut fraction = #round;
+                        if fraction >> #fraction_bits != 0 {
+                            fraction >>= 1;
+                            biased += 1;
+                        }
+                        if biased > #max_exponent {
+                            #error
+                        }
+                        // VAX floats have no subnormal numbers
+                        match biased < 1 {
+                            true => 0,
+                            false => ((negative as u64) << #sign_shift) | ((biased as u64) << (#fraction_bits - 1)) | (fraction & ((1u64 << (#fraction_bits - 1)) - 1)),
+                        }
+                    };
+                    #(writer.write_u16::<byteorder::LittleEndian>((bits >> #shifts) as u16)?;)*
+                }
+            }
+        }
+    }
+}
+
+// writes the integer `value` of type t as decimal digits, negative and too large values are rejected
+pub(crate) fn quote_serialize_decimal(t: &IntegerType, format: &DecimalFormat, name: &Ident, value: &TokenStream2, endianness: &TokenStream2) -> TokenStream2 {
+    let ty: Ident = t.into();
+    let width = format.size() as usize;
+    let digits = format.digits();
+    let kind = match format {
+        DecimalFormat::Bcd(_) => "BCD",
+        DecimalFormat::Ascii(_) => "ASCII",
+    };
+    let message = quote_message(
+        &format!("'{}' {{}} does not fit into {} {} digits", name, digits, kind),
+        quote! { value },
+        &format!("'{}' does not fit into {} {} digits", name, digits, kind),
+    );
+    let error = quote! {
+        return Err(#IO::Error::new(#IO::ErrorKind::InvalidInput, #message));
+    };
+
+    let check_negative = match t.is_signed() {
+        true => quote! {
+            if value < 0 {
+                #error
+            }
+        },
+        false => quote! {},
+    };
+    // larger widths cover all values of an u128
+    let check_digits = match 10u128.checked_pow(digits) {
+        Some(limit) => quote! {
+            if value as u128 >= #limit {
+                #error
+            }
+        },
+        None => quote! {},
+    };
+
+    let fill = match format {
+        DecimalFormat::Bcd(_) => {
+            let reverse = match endianness.to_string().as_str() {
+                "LittleEndian" => quote! { bytes.reverse(); },
+                _ => quote! {},
+            };
+            quote! {
+                let mut bytes = [0u8; #width];
+                for byte in bytes.iter_mut().rev() {
+                    *byte = (((digits / 10 % 10) << 4) | (digits % 10)) as u8;
+                    digits /= 100;
+                }
+                #reverse
+            }
+        }
+        DecimalFormat::Ascii(_) => quote! {
+            let mut bytes = [b'0'; #width];
+            for byte in bytes.iter_mut().rev() {
+                *byte = b'0' + (digits % 10) as u8;
+                digits /= 10;
+            }
+        },
+    };
+
+    quote! {
+        {
+            let value: #ty = #value;
+            #check_negative
+            #check_digits
+            let mut digits = value as u128;
+            #fill
+            #IO::Write::write_all(writer, &bytes)?;
+        }
+    }
+}
+
+// calculated value of the attribute converted to the type of the field
+fn quote_calc_value(attribute: &DatatypeAttribute) -> TokenStream2 {
+    let expr = match &attribute.calc {
+        Some(calc) => &calc.expr,
+        None => return quote! {},
+    };
+    let ty = match &attribute.ty {
+        DatatypeAttributeType::PrimitiveInteger(t) | DatatypeAttributeType::VarInt(t, _) | DatatypeAttributeType::Decimal(t, _) => {
+            let ty: Ident = t.into();
+            quote! { #ty }
+        }
+        DatatypeAttributeType::PrimitiveFloat(t) => {
+            let ty: &str = t.clone().into();
+            ty.parse().unwrap()
+        }
+        _ => return quote! {},
+    };
+
+    quote! {
+        {
+            let value: #ty = #expr;
+            value
+        }
+    }
+}
+
+// writes the calculated value instead of the value of the field
+fn quote_serialize_calc(attribute: &DatatypeAttribute, endianness: &TokenStream2) -> TokenStream2 {
+    let value = quote_calc_value(attribute);
+
+    match &attribute.ty {
+        DatatypeAttributeType::PrimitiveInteger(t) => {
+            let write = quote_serialize_integer(t, endianness, &quote! { value });
+            quote! {
+                let value = #value;
+                #write
+            }
+        }
+        DatatypeAttributeType::VarInt(t, encoding) => quote_serialize_varint(encoding, t, &value),
+        DatatypeAttributeType::PrimitiveFloat(t) => quote_serialize_primitive_float(t, &value, endianness),
+        DatatypeAttributeType::Decimal(t, format) => quote_serialize_decimal(t, format, &attribute.name, &value, endianness),
+        // other types are already reported while parsing
+        _ => quote! {},
+    }
+}
+pub(crate) fn quote_serialize_integer(t: &IntegerType, endianness: &TokenStream2, value: &TokenStream2) -> TokenStream2 {
+    let ty: &str = t.clone().into();
+    let write = format_ident!("write_{}", ty);
+
+    match t {
+        IntegerType::U8 | IntegerType::I8 => quote! { writer.#write(#value)?; },
+        _ => quote! { writer.#write::<byteorder::#endianness>(#value)?; },
+    }
+}
+
+// maps the char `c` to a byte of a single byte charset
+fn quote_encode_single_byte(encoding: &TextEncoding, name: &Ident) -> TokenStream2 {
+    let charset: &str = (&encoding.charset).into();
+    let map = match encoding.charset {
+        Charset::Ascii => quote! { if (c as u32) < 0x80 { Some(c as u8) } else { None } },
+        Charset::Windows1252 => {
+            let table = WINDOWS_1252.iter();
+            quote! {
+                match c as u32 {
+                    0..=0x7f | 0xa0..=0xff => Some(c as u8),
+                    code => [#(#table),*].iter().position(|x| *x as u32 == code).map(|i| i as u8 + 0x80),
+                }
+            }
+        }
+        _ => quote! { u8::try_from(c).ok() },
+    };
+
+    match encoding.lossy {
+        true => quote! { (#map).unwrap_or(b'?') },
+        false => {
+            let message = format!("'{}' contains characters that cannot be encoded as {}", name, charset);
+            quote! {
+                (#map).ok_or_else(|| #IO::Error::new(#IO::ErrorKind::InvalidInput, #message))?
+            }
+        }
+    }
+}
+
+fn quote_serialize_primitive_string(
+    format: &StringFormat,
+    encoding: &TextEncoding,
     name: &Ident,
-    size: &Option<u8>,
-    _: &DatatypeAttribute,
+    value: &TokenStream2,
+    endianness: &TokenStream2,
 ) -> TokenStream2 {
-    // TODO: refactor this so that Vec<u8> etc types can be serialized as well.
+    let unit_size = encoding.charset.unit_size();
+    let bytes = match encoding.charset {
+        Charset::Utf8 => quote! { let bytes = #value.as_bytes(); },
+        Charset::Ascii | Charset::Latin1 | Charset::Windows1252 => {
+            let map = quote_encode_single_byte(encoding, name);
+            quote! {
+                let bytes = #value
+                    .chars()
+                    .map(|c| -> #IO::Result<u8> {
+                        let byte = #map;
+                        Ok(byte)
+                    })
+                    .collect::<#IO::Result<#VEC<u8>>>()?;
+                let bytes = &bytes[..];
+            }
+        }
+        Charset::Utf16Le => quote! {
+            let bytes: #VEC<u8> = #value.encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect();
+            let bytes = &bytes[..];
+        },
+        Charset::Utf16Be => quote! {
+            let bytes: #VEC<u8> = #value.encode_utf16().flat_map(|unit| unit.to_be_bytes()).collect();
+            let bytes = &bytes[..];
+        },
+    };
 
-    /*
-    if let Ok(_) = IntegerType::try_from(ty) {
-        //let ty : &str = t.clone().into();
-        let write = format_ident!("write_{}", ty);
+    let write = match format {
+        StringFormat::Terminated(terminator) => {
+            let message = format!("String '{}' contains its terminator {:#04x}", name, terminator);
+            let terminator = encoding.charset.unit(*terminator);
+
+            quote! {
+                let terminator = [#(#terminator),*];
+                if bytes.chunks_exact(#unit_size).any(|unit| unit == terminator) {
+                    return Err(#IO::Error::new(#IO::ErrorKind::InvalidInput, #message));
+                }
+                #IO::Write::write_all(writer, bytes)?;
+                #IO::Write::write_all(writer, &terminator)?;
+            }
+        }
+        StringFormat::Prefixed(t, varint) => {
+            let length = quote_string_length(encoding, &quote! { bytes.len() });
+            let write_length = match varint {
+                Some(varint) => quote_serialize_varint(varint, t, &length),
+                None => {
+                    let ty: Ident = t.into();
+                    let message = format!("String '{}' is too long for a {} length prefix", name, ty);
+                    let write_length = quote_serialize_integer(t, endianness, &quote! { length });
 
-        emit_error!(Span::call_site(), "{:?}", attribute);
-        //return quote !{
-        //    writer.#write(#)
-        //}
-    }*/
+                    quote! {
+                        let length = #ty::try_from(#length)
+                            .map_err(|_| #IO::Error::new(#IO::ErrorKind::InvalidInput, #message))?;
+                        #write_length
+                    }
+                }
+            };
 
-    match size {
-        Some(_) => quote! {
-            for i in 0..#size {
-                entry.serialize(writer)?;
+            quote! {
+                #write_length
+                #IO::Write::write_all(writer, bytes)?;
+            }
+        }
+        StringFormat::Fixed { width, pad, truncate } => {
+            // never cut a character in half
+            let end = match encoding.charset {
+                Charset::Utf8 => quote! {
+                    let mut end = #width;
+                    while end > 0 && bytes[end] & 0xc0 == 0x80 {
+                        end -= 1;
+                    }
+                },
+                Charset::Ascii | Charset::Latin1 | Charset::Windows1252 => quote! {
+                    let end = #width;
+                },
+                Charset::Utf16Le | Charset::Utf16Be => {
+                    let from_bytes = match encoding.charset {
+                        Charset::Utf16Le => quote! { u16::from_le_bytes },
+                        _ => quote! { u16::from_be_bytes },
+                    };
+                    quote! {
+                        let mut end = #width;
+                        if end >= 2 && (0xd800..0xdc00).contains(&#from_bytes([bytes[end - 2], bytes[end - 1]])) {
+                            end -= 2;
+                        }
+                    }
+                }
+            };
+            let too_long = match truncate {
+                true => quote! {
+                    {
+                        #end
+                        &bytes[..end]
+                    }
+                },
+                false => {
+                    let message = format!("String '{}' exceeds its fixed width of {} bytes", name, width);
+                    quote! { return Err(#IO::Error::new(#IO::ErrorKind::InvalidInput, #message)) }
+                }
+            };
+            let pad = encoding.charset.unit(*pad);
+
+            quote! {
+                let bytes = match bytes.len() > #width {
+                    true => #too_long,
+                    false => bytes,
+                };
+                #IO::Write::write_all(writer, bytes)?;
+                for _ in (bytes.len()..#width).step_by(#unit_size) {
+                    #IO::Write::write_all(writer, &[#(#pad),*])?;
+                }
+            }
+        }
+    };
+
+    quote! {
+        {
+            #bytes
+            #write
+        }
+    }
+}
+
+fn quote_serialize_primitive_char(encoding: &TextEncoding, name: &Ident) -> TokenStream2 {
+    match encoding.charset {
+        Charset::Utf8 => quote! {
+            {
+                let mut buffer = [0u8; 4];
+                #IO::Write::write_all(writer, self.#name.encode_utf8(&mut buffer).as_bytes())?;
             }
         },
-        None => quote! {
-            for entry in &self.#name {
-                entry.serialize(writer)?;
+        Charset::Ascii | Charset::Latin1 | Charset::Windows1252 => {
+            let map = quote_encode_single_byte(encoding, name);
+            quote! {
+                {
+                    let c = self.#name;
+                    writer.write_u8(#map)?;
+                }
+            }
+        }
+        Charset::Utf16Le | Charset::Utf16Be => {
+            let endianness = match encoding.charset {
+                Charset::Utf16Le => quote! { LittleEndian },
+                _ => quote! { BigEndian },
+            };
+            quote! {
+                {
+                    let mut buffer = [0u16; 2];
+                    for unit in self.#name.encode_utf16(&mut buffer) {
+                        writer.write_u16::<byteorder::#endianness>(*unit)?;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// writes the collection element `entry` which is a reference
+fn quote_serialize_element(embedded_ty: &str, attribute: &DatatypeAttribute, endianness: &TokenStream2) -> TokenStream2 {
+    if let Some((format, encoding)) = &attribute.element_string {
+        return quote_serialize_primitive_string(format, encoding, &attribute.name, &quote! { entry }, endianness);
+    }
+
+    if let Ok(t) = IntegerType::try_from(embedded_ty) {
+        return quote_serialize_integer(&t, endianness, &quote! { *entry });
+    }
+
+    if let Ok(t) = FloatType::try_from(embedded_ty) {
+        let ty: &str = t.into();
+        let write = format_ident!("write_{}", ty);
+        return quote! { writer.#write::<byteorder::#endianness>(*entry)?; };
+    }
+
+    quote! { crate::datatypes::WriteTo::write_to(entry, writer)?; }
+}
+
+fn quote_serialize_primitive_collection(
+    embedded_ty: &str,
+    attribute: &DatatypeAttribute,
+    bound: &CollectionBound,
+    endianness: &TokenStream2,
+) -> TokenStream2 {
+    let name = &attribute.name;
+    let write = quote_serialize_element(embedded_ty, attribute, endianness);
+
+    match bound {
+        CollectionBound::Terminator(terminator) => {
+            let ty: TokenStream2 = embedded_ty.parse().unwrap();
+            let message = format!("'{}' contains its terminator", name);
+
+            quote! {
+                for entry in self.#name.iter() {
+                    if *entry == (#terminator) {
+                        return Err(#IO::Error::new(#IO::ErrorKind::InvalidInput, #message));
+                    }
+                    #write
+                }
+                {
+                    let terminator: #ty = #terminator;
+                    let entry = &terminator;
+                    #write
+                }
+            }
+        }
+        _ => quote! {
+            for entry in self.#name.iter() {
+                #write
             }
         },
     }
@@ -130,20 +909,211 @@ fn quote_serialize_primitive_collection(
 
 fn quote_serialize_primitive_collection_len(
     t: &IntegerType,
+    varint: &Option<VarintEncoding>,
+    unit: &LengthUnit,
     name: &Ident,
+    length: &TokenStream2,
     endianness: &TokenStream2,
 ) -> TokenStream2 {
-    let ty: &str = t.clone().into();
-    let write = format_ident!("write_{}", ty);
+    if let Some(encoding) = varint {
+        return quote_serialize_varint(encoding, t, length);
+    }
 
-    let ty = format_ident!("{}", ty);
-    match t {
-        IntegerType::U8 | IntegerType::I8 => quote! { writer.#write(self.#name.len() as #ty)?; },
-        _ => quote! {writer.#write::<byteorder::#endianness>(self.#name.len() as #ty)?; },
+    let ty: Ident = t.into();
+    let message = match unit {
+        LengthUnit::Elements => format!("'{}' is too long for a {} length", name, ty),
+        LengthUnit::Bytes => format!("'{}' is too long for a {} byte length", name, ty),
+    };
+    let write = quote_serialize_integer(t, endianness, &quote! { length });
+
+    quote! {
+        let length = #ty::try_from(#length)
+            .map_err(|_| #IO::Error::new(#IO::ErrorKind::InvalidInput, #message))?;
+        #write
+    }
+}
+
+fn quote_serialize_checksum(
+    t: &IntegerType,
+    algorithm: &ChecksumAlgorithm,
+    range: Option<(usize, usize)>,
+    endianness: &TokenStream2,
+) -> TokenStream2 {
+    // an invalid range is already reported by the validation
+    let (start, end) = match range {
+        Some((start, end)) => (offset_ident(start), offset_ident(end)),
+        None => return quote! {},
+    };
+    let ty: Ident = t.into();
+    let checksum = quote_checksum(algorithm);
+    let write = quote_serialize_integer(t, endianness, &quote! { checksum });
+
+    quote! {
+        let checksum = {
+            let data: &[u8] = &writer.get_ref()[#start..#end];
+            #checksum
+        } as #ty;
+        #write
+    }
+}
+
+// fields from the start of the first checksum range up to the last checksum are written to a buffer
+// so that the checksums are calculated from the bytes that are actually written
+fn quote_serialize_checksum_buffer(attrs: &[DatatypeAttribute], serialize_impl: &[TokenStream2]) -> TokenStream2 {
+    let ranges = checksum_ranges(attrs);
+    let first = ranges.iter().map(|(_, start, _)| *start).min();
+    let last = ranges.iter().map(|(index, _, _)| *index).max();
+
+    let (first, last) = match (first, last) {
+        (Some(first), Some(last)) if first <= last => (first, last),
+        _ => return quote! { #(#serialize_impl)* },
+    };
+
+    let offsets: Vec<usize> = ranges.iter().flat_map(|(_, start, end)| [*start, *end]).collect();
+    let buffered = (first..=last).map(|index| {
+        let write = &serialize_impl[index];
+        match offsets.contains(&index) {
+            true => {
+                let offset = offset_ident(index);
+                quote! {
+                    let #offset = writer.get_ref().len();
+                    #write
+                }
+            }
+            false => write.clone(),
+        }
+    });
+    let before = &serialize_impl[..first];
+    let after = &serialize_impl[last + 1..];
+
+    quote! {
+        #(#before)*
+        {
+            let mut buffer = #IO::Cursor::new(#VEC::<u8>::new());
+            {
+                let writer = &mut buffer;
+                #(#buffered)*
+            }
+            #IO::Write::write_all(writer, buffer.get_ref())?;
+        }
+        #(#after)*
     }
 }
 
-pub(crate) fn produce_serialize_impl(name: &Ident, attrs: &Vec<DatatypeAttribute>) -> TokenStream2 {
+// writes the zero bytes, `track` updates the offset within the datatype afterwards
+fn quote_serialize_padding(padding: &Padding, track: bool) -> TokenStream2 {
+    let amount = match padding {
+        Padding::Fixed(amount) => {
+            let amount = *amount as u64;
+            quote! { #amount }
+        }
+        Padding::Align(align) => quote_alignment(*align, &quote! { offset }),
+    };
+    let track = match track {
+        true => quote! { offset += padding; },
+        false => quote! {},
+    };
+
+    quote! {
+        let padding = #amount;
+        // written in chunks from a zero buffer, so padding does not allocate
+        let mut remaining = padding;
+        while remaining > 0 {
+            let chunk = remaining.min(16);
+            #IO::Write::write_all(writer, &[0u8; 16][..chunk as usize])?;
+            remaining -= chunk;
+        }
+        #track
+    }
+}
+
+// sum of the sizes of the given attributes as u32. The size of aligned datatypes depends on the
+// offset of each field and is therefore summed up step by step.
+fn quote_serialize_size_sum(attrs: &[DatatypeAttribute], size_impl: &[TokenStream2]) -> TokenStream2 {
+    if last_alignment(attrs).is_none() {
+        return quote! { (0 #(+ #size_impl)*) };
+    }
+
+    let steps = attrs.iter().zip(size_impl).map(|(attribute, size)| match &attribute.ty {
+        DatatypeAttributeType::Padding(Padding::Align(align)) => {
+            let padding = quote_alignment(*align, &quote! { offset });
+            quote! { offset += #padding; }
+        }
+        _ => quote! { offset += (#size) as u64; },
+    });
+
+    quote! {
+        {
+            let mut offset: u64 = 0;
+            #(#steps)*
+            offset as u32
+        }
+    }
+}
+
+// writes the offset of the addressed field `addressed` held by the variable `<addressed>_offset`
+fn quote_serialize_offset(t: &IntegerType, endianness: &TokenStream2, addressed: &Ident, field: &Ident) -> TokenStream2 {
+    let ty: Ident = t.into();
+    let offset = format_ident!("{}_offset", addressed);
+    let message = format!("The offset of '{}' does not fit into '{}'", addressed, field);
+    let write = quote_serialize_integer(t, endianness, &quote! { value });
+
+    quote! {
+        let value = #ty::try_from(#offset)
+            .map_err(|_| #IO::Error::new(#IO::ErrorKind::InvalidInput, #message))?;
+        #write
+    }
+}
+
+// addressed fields are written after all other fields, their offsets are calculated upfront from
+// the sizes of the fields
+fn quote_serialize_addressed_offsets(attrs: &[DatatypeAttribute], size_impl: &[TokenStream2]) -> TokenStream2 {
+    let first = match attrs.iter().position(|x| x.address.is_some()) {
+        Some(first) => first,
+        None => return quote! {},
+    };
+    let fixed_size = quote_serialize_size_sum(&attrs[..first], &size_impl[..first]);
+
+    let mut preceding: Vec<&TokenStream2> = vec![];
+    let mut offsets = vec![];
+    let mut stream = false;
+    for (index, attribute) in attrs.iter().enumerate().skip(first) {
+        let address = match &attribute.address {
+            Some(address) => address,
+            None => continue,
+        };
+
+        if index == first || attrs[index - 1].name != attribute.name {
+            let offset = format_ident!("{}_offset", attribute.name);
+            let base = match address.base {
+                OffsetBase::Start => quote! {},
+                OffsetBase::Stream => {
+                    stream = true;
+                    quote! { stream_position + }
+                }
+            };
+
+            offsets.push(quote! {
+                let #offset: u64 = #base fixed_size #(+ (#preceding) as u64)*;
+            });
+        }
+
+        preceding.push(&size_impl[index]);
+    }
+
+    let stream_position = match stream {
+        true => quote! { let stream_position = #IO::Seek::stream_position(writer)?; },
+        false => quote! {},
+    };
+
+    quote! {
+        #stream_position
+        let fixed_size = (#fixed_size) as u64;
+        #(#offsets)*
+    }
+}
+
+pub(crate) fn produce_serialize_impl(name: &Ident, generics: &Generics, attrs: &[DatatypeAttribute], align_to_stream: bool, fixed_size: bool) -> TokenStream2 {
     let size_impl = attrs
         .iter()
         .map(|attribute| {
@@ -153,65 +1123,237 @@ pub(crate) fn produce_serialize_impl(name: &Ident, attrs: &Vec<DatatypeAttribute
                 DatatypeAttributeType::PrimitiveInteger(t) => {
                     quote_serialize_size_primitive_integer(t)
                 }
+                DatatypeAttributeType::VarInt(t, encoding) => match (&attribute.length_of, attribute.reserved) {
+                    _ if attribute.calc.is_some() => {
+                        quote_serialize_size_varint(encoding, t, &quote_calc_value(attribute))
+                    }
+                    (Some((collection, unit)), _) => {
+                        quote_serialize_size_varint(encoding, t, &quote_collection_length(attrs, collection, unit))
+                    }
+                    (None, true) => quote! { 1u32 },
+                    (None, false) => quote_serialize_size_varint(encoding, t, &quote! { self.#attribute_name }),
+                },
                 DatatypeAttributeType::PrimitiveFloat(t) => quote_serialize_size_primitive_float(t),
-                DatatypeAttributeType::String => {
-                    quote_serialize_size_primitive_string(&attribute_name)
+                DatatypeAttributeType::Scaled(_, scaling) => quote_serialize_size_primitive_integer(&scaling.raw),
+                DatatypeAttributeType::Decimal(_, format) => {
+                    let size = format.size();
+                    quote! { #size }
+                }
+                DatatypeAttributeType::EncodedFloat(_, encoding) => {
+                    let size = encoding.size();
+                    quote! { #size }
+                }
+                DatatypeAttributeType::String(format, encoding, _) => {
+                    quote_serialize_size_primitive_string(format, encoding, &quote! { self.#attribute_name })
                 }
-                DatatypeAttributeType::CollectionLength(t) => {
+                DatatypeAttributeType::Char(encoding) => {
+                    quote_serialize_size_primitive_char(encoding, &attribute_name)
+                }
+                DatatypeAttributeType::CollectionLength(t, None, _) => {
                     quote_serialize_size_primitive_integer(t)
                 }
-                DatatypeAttributeType::Collection(embedded_type, size) => {
-                    quote_serialize_size_primitive_collection(embedded_type, size, &attribute_name)
+                DatatypeAttributeType::CollectionLength(t, Some(encoding), unit) => {
+                    quote_serialize_size_varint(encoding, t, &quote_collection_length(attrs, &attribute_name, unit))
+                }
+                DatatypeAttributeType::Collection(embedded_type, size, bound, _) => {
+                    let entries = quote_serialize_size_primitive_collection(embedded_type, size, &attribute_name, &attribute.element_string);
+                    match bound {
+                        CollectionBound::Terminator(terminator) => {
+                            let terminator = quote_serialize_size_terminator(embedded_type, terminator);
+                            quote! { #entries + #terminator }
+                        }
+                        _ => entries,
+                    }
                 }
                 DatatypeAttributeType::Struct(_) => {
                     quote_serialize_size_primitive_struct(&attribute_name)
                 }
+                DatatypeAttributeType::Magic(magic) => {
+                    let size = magic.len() as u32;
+                    quote! { #size }
+                }
+                DatatypeAttributeType::Checksum(t, _, _) => quote_serialize_size_primitive_integer(t),
+                DatatypeAttributeType::Offset(t) => quote_serialize_size_primitive_integer(t),
+                DatatypeAttributeType::Padding(Padding::Fixed(amount)) => quote! { #amount },
+                // only used within quote_serialize_size_aligned which calculates the alignment itself
+                DatatypeAttributeType::Padding(Padding::Align(_)) => quote! { 0u32 },
             }
         })
         .collect::<Vec<_>>();
 
+    // the size of datatypes without alignments is simply the sum of all fields
+    let last_alignment = last_alignment(attrs);
+    // statically sized datatypes return their SIZE constant
+    let size = match fixed_size {
+        true => quote! { Self::SIZE as u32 },
+        false => quote_serialize_size_sum(attrs, &size_impl),
+    };
+    let addressed_offsets = quote_serialize_addressed_offsets(attrs, &size_impl);
+
     let serialize_impl = attrs
         .iter()
-        .map(|attribute| {
+        .enumerate()
+        .map(|(index, attribute)| {
             let endianness: &str = (&attribute.endianness).into();
             let endianness: proc_macro2::TokenStream = endianness.parse().unwrap();
+            let length = attribute
+                .length_of
+                .as_ref()
+                .map(|(collection, unit)| quote_collection_length(attrs, collection, unit));
+            if attribute.calc.is_some() {
+                return quote_serialize_calc(attribute, &endianness);
+            }
+
             match &attribute.ty {
-                DatatypeAttributeType::PrimitiveInteger(t) => {
-                    quote_serialize_primitive_integer(attribute, t, &endianness)
+                DatatypeAttributeType::PrimitiveInteger(t) => match &attribute.offset_of {
+                    Some(addressed) => quote_serialize_offset(t, &endianness, addressed, &attribute.name),
+                    None => quote_serialize_primitive_integer(attribute, t, &endianness, &length),
+                },
+                DatatypeAttributeType::Offset(t) => {
+                    quote_serialize_offset(t, &endianness, &attribute.name, &attribute.name)
+                }
+                DatatypeAttributeType::VarInt(t, encoding) => {
+                    let name = &attribute.name;
+                    match (&length, attribute.reserved) {
+                        (Some(length), _) => quote_serialize_varint(encoding, t, length),
+                        (None, true) => quote! { writer.write_u8(0)?; },
+                        (None, false) => quote_serialize_varint(encoding, t, &quote! { self.#name }),
+                    }
                 }
                 DatatypeAttributeType::PrimitiveFloat(t) => {
-                    quote_serialize_primitive_float(t, &attribute.name, &endianness)
+                    let name = &attribute.name;
+                    quote_serialize_primitive_float(t, &quote! { self.#name }, &endianness)
+                }
+                DatatypeAttributeType::Scaled(_, scaling) => {
+                    let name = &attribute.name;
+                    quote_serialize_scaled(scaling, name, &quote! { self.#name }, &endianness)
+                }
+                DatatypeAttributeType::EncodedFloat(_, encoding) => {
+                    let name = &attribute.name;
+                    quote_serialize_encoded_float(encoding, name, &quote! { self.#name }, &endianness)
+                }
+                DatatypeAttributeType::Decimal(t, format) => {
+                    let name = &attribute.name;
+                    let value = match (&attribute.length_of, &length, attribute.reserved) {
+                        (Some((collection, _)), Some(length), _) => {
+                            let ty: Ident = t.into();
+                            let message = format!("'{}' is too long for its length field '{}'", collection, name);
+                            quote! {
+                                #ty::try_from(#length)
+                                    .map_err(|_| #IO::Error::new(#IO::ErrorKind::InvalidInput, #message))?
+                            }
+                        }
+                        (_, _, true) => quote! { 0 },
+                        _ => quote! { self.#name },
+                    };
+                    quote_serialize_decimal(t, format, name, &value, &endianness)
                 }
                 DatatypeAttributeType::Struct(_) => {
                     let name = &attribute.name;
                     quote! {
-                        self.#name.serialize(writer)?; 
+                        crate::datatypes::WriteTo::write_to(&self.#name, writer)?;
                     }
                 },
-                DatatypeAttributeType::String => quote_serialize_primitive_string(),
-                DatatypeAttributeType::Collection(ty, size) => {
-                    quote_serialize_primitive_collection(ty, &attribute.name, size, attribute)
+                DatatypeAttributeType::String(format, encoding, _) => {
+                    let name = &attribute.name;
+                    quote_serialize_primitive_string(format, encoding, name, &quote! { self.#name }, &endianness)
                 }
-                DatatypeAttributeType::CollectionLength(t) => {
-                    quote_serialize_primitive_collection_len(t, &attribute.name, &endianness)
+                DatatypeAttributeType::Char(encoding) => {
+                    quote_serialize_primitive_char(encoding, &attribute.name)
+                }
+                DatatypeAttributeType::Collection(ty, _, bound, _) => {
+                    quote_serialize_primitive_collection(ty, attribute, bound, &endianness)
+                }
+                DatatypeAttributeType::CollectionLength(t, varint, unit) => {
+                    let length = quote_collection_length(attrs, &attribute.name, unit);
+                    quote_serialize_primitive_collection_len(t, varint, unit, &attribute.name, &length, &endianness)
+                }
+                DatatypeAttributeType::Magic(magic) => quote! {
+                    #IO::Write::write_all(writer, &[#(#magic),*])?;
+                },
+                DatatypeAttributeType::Checksum(t, algorithm, range) => {
+                    quote_serialize_checksum(t, algorithm, checksum_range(attrs, index, range), &endianness)
+                }
+                DatatypeAttributeType::Padding(padding) => {
+                    quote_serialize_padding(padding, last_alignment.is_some_and(|last| index < last))
                 }
             }
         })
         .collect::<Vec<_>>();
 
-    quote! {
-        impl crate::datatypes::Serialize for #name {
+    // tracks the offset within the datatype up to the last alignment
+    let serialize_impl = match last_alignment {
+        Some(last) => serialize_impl
+            .into_iter()
+            .zip(&size_impl)
+            .enumerate()
+            .map(|(index, (write, size))| match &attrs[index].ty {
+                DatatypeAttributeType::Padding(_) => write,
+                _ if index < last => quote! {
+                    #write
+                    offset += (#size) as u64;
+                },
+                _ => write,
+            })
+            .collect::<Vec<_>>(),
+        None => serialize_impl,
+    };
+    let offset = match (last_alignment, align_to_stream) {
+        (Some(_), true) => quote! { let mut offset: u64 = #IO::Seek::stream_position(writer)?; },
+        (Some(_), false) => quote! { let mut offset: u64 = 0; },
+        (None, _) => quote! {},
+    };
+    let serialize_impl = quote_serialize_checksum_buffer(attrs, &serialize_impl);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // size() returns the SIZE constant of statically sized datatypes without looking at the fields, debug
+    // builds check that it matches what was written, e.g. for hand-written FixedSize implementations
+    let message = format!("'{}' wrote a different amount of bytes than its SIZE", name);
+    let (size_start, size_check) = match fixed_size {
+        true => (
+            quote! {
+                #[cfg(debug_assertions)]
+                let size_start = #IO::Seek::stream_position(writer)?;
+            },
+            quote! {
+                #[cfg(debug_assertions)]
+                assert_eq!(#IO::Seek::stream_position(writer)? - size_start, Self::SIZE as u64, #message);
+            },
+        ),
+        false => (quote! {}, quote! {}),
+    };
 
-            fn serialize(&self, writer: &mut std::io::BufWriter<std::fs::File>) -> std::io::Result<()> {
-                use byteorder::{BigEndian, WriteBytesExt};
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Serializes the datatype into any seekable writer
+            pub fn write_to<W: #IO::Write + #IO::Seek>(&self, writer: &mut W) -> #IO::Result<()> {
+                use byteorder::BigEndian;
+                use #BYTES_EXT::WriteBytesExt;
 
-                #(#serialize_impl)*
+                #size_start
+                #offset
+                #addressed_offsets
+                #serialize_impl
+                #size_check
 
                 Ok(())
             }
+        }
+
+        impl #impl_generics crate::datatypes::WriteTo for #name #ty_generics #where_clause {
+            fn write_to<W: #IO::Write + #IO::Seek>(&self, writer: &mut W) -> #IO::Result<()> {
+                Self::write_to(self, writer)
+            }
+        }
+
+        impl #impl_generics crate::datatypes::Serialize for #name #ty_generics #where_clause {
+
+            fn serialize(&self, writer: &mut #WRITER) -> #IO::Result<()> {
+                self.write_to(writer)
+            }
 
             fn size(&self) -> u32 {
-                #(#size_impl) +*
+                #size
             }
         }
     }
@@ -0,0 +1,139 @@
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use proc_macro_error::emit_error;
+use syn::Generics;
+
+use crate::{
+    data_struct::{
+        generate::{quote_alignment, serialize::primitive_size},
+        structs::{DataStructArg, DataStructArgs},
+    },
+    structs::{CollectionBound, DatatypeAttribute, DatatypeAttributeType, Padding},
+    types::string::{Charset, StringFormat},
+};
+
+// size of a nested datatype, only known at compile time if it implements FixedSize
+fn quote_nested_size(ty: &str) -> TokenStream2 {
+    let ty: TokenStream2 = ty.parse().unwrap();
+    quote! { <#ty as crate::datatypes::FixedSize>::SIZE }
+}
+
+// size of the attribute as constant expression, None if it depends on the value. Nested datatypes
+// are only considered if `nested` is set as it is unknown whether they are statically sized.
+pub(crate) fn quote_static_size(attribute: &DatatypeAttribute, nested: bool) -> Option<TokenStream2> {
+    let size = match &attribute.ty {
+        DatatypeAttributeType::PrimitiveInteger(t) |
+        DatatypeAttributeType::Checksum(t, _, _) |
+        DatatypeAttributeType::Offset(t) |
+        DatatypeAttributeType::CollectionLength(t, None, _) => t.size() as usize,
+        DatatypeAttributeType::PrimitiveFloat(t) => t.size() as usize,
+        DatatypeAttributeType::Scaled(_, scaling) => scaling.raw.size() as usize,
+        DatatypeAttributeType::EncodedFloat(_, encoding) => encoding.size() as usize,
+        DatatypeAttributeType::Decimal(_, format) => format.size() as usize,
+        DatatypeAttributeType::Magic(magic) => magic.len(),
+        DatatypeAttributeType::Padding(Padding::Fixed(amount)) => *amount as usize,
+        DatatypeAttributeType::String(StringFormat::Fixed { width, .. }, _, _) => *width,
+        DatatypeAttributeType::Char(encoding) => match encoding.charset {
+            Charset::Ascii | Charset::Latin1 | Charset::Windows1252 => 1,
+            _ => return None,
+        },
+        // arrays always (de-)serialize all of their elements
+        DatatypeAttributeType::Collection(ty, Some(length), bound, _) => {
+            let entries = match bound {
+                CollectionBound::Terminator(_) => *length as usize + 1,
+                _ => *length as usize,
+            };
+            return match primitive_size(ty) {
+                Some(size) => {
+                    let size = entries * size as usize;
+                    Some(quote! { #size })
+                }
+                None if nested => {
+                    let size = quote_nested_size(ty);
+                    Some(quote! { #entries * #size })
+                }
+                None => None,
+            };
+        }
+        DatatypeAttributeType::Struct(ty) if nested => return Some(quote_nested_size(ty)),
+        // alignment is added by quote_fixed_size
+        DatatypeAttributeType::Padding(Padding::Align(_)) => 0,
+        DatatypeAttributeType::VarInt(_, _) |
+        DatatypeAttributeType::CollectionLength(_, Some(_), _) |
+        DatatypeAttributeType::String(_, _, _) |
+        DatatypeAttributeType::Collection(_, None, _, _) |
+        DatatypeAttributeType::Struct(_) => return None,
+    };
+
+    Some(quote! { #size })
+}
+
+// size of the datatype as constant expression if all attributes are statically sized, alignments
+// are only static if they are relative to the start of the datatype
+pub(crate) fn quote_fixed_size(attrs: &[DatatypeAttribute], nested: bool, align_to_stream: bool) -> Result<TokenStream2, &DatatypeAttribute> {
+    let steps = attrs
+        .iter()
+        .map(|attribute| match &attribute.ty {
+            DatatypeAttributeType::Padding(Padding::Align(_)) if align_to_stream => Err(attribute),
+            DatatypeAttributeType::Padding(Padding::Align(align)) => {
+                let padding = quote_alignment(*align, &quote! { offset });
+                Ok(quote! { offset += #padding; })
+            }
+            _ => match quote_static_size(attribute, nested) {
+                Some(size) => Ok(quote! { offset += (#size) as u64; }),
+                None => Err(attribute),
+            },
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(quote! {
+        {
+            let mut offset: u64 = 0;
+            #(#steps)*
+            offset as usize
+        }
+    })
+}
+
+// SIZE constant and FixedSize implementation for statically sized datatypes, `fixed_size` requires
+// the datatype to be statically sized which allows nested datatypes and checks the expected size
+pub(crate) fn produce_fixed_size_impl(name: &Ident, generics: &Generics, attrs: &[DatatypeAttribute], args: &DataStructArgs, align_to_stream: bool) -> Option<TokenStream2> {
+    let expected = args.0.iter().find_map(|x| match x {
+        DataStructArg::FixedSize(expected) => Some(expected),
+        _ => None,
+    });
+
+    let size = match (quote_fixed_size(attrs, expected.is_some(), align_to_stream), expected) {
+        (Ok(size), _) => size,
+        (Err(_), None) => return None,
+        (Err(attribute), Some(_)) => {
+            emit_error!(attribute.name.span(), "'{}' is not statically sized which is required by 'fixed_size'", attribute.name);
+            return None;
+        }
+    };
+
+    let check = match expected {
+        Some(Some(expected)) => {
+            // the expected size is given in bits
+            let message = format!("The size of '{}' does not match its fixed_size of {} bits", name, expected);
+            quote! {
+                const _: () = assert!(#name::SIZE * 8 == #expected, #message);
+            }
+        }
+        _ => quote! {},
+    };
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Some(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Size in bytes of the serialized datatype
+            pub const SIZE: usize = #size;
+        }
+
+        impl #impl_generics crate::datatypes::FixedSize for #name #ty_generics #where_clause {
+            const SIZE: usize = #name::SIZE;
+        }
+
+        #check
+    })
+}
@@ -0,0 +1,133 @@
+use proc_macro2::{Ident, TokenStream as TokenStream2, TokenTree};
+use syn::Generics;
+
+use crate::{
+    generate::{quote_message, IO},
+    structs::{Constraint, DatatypeAttribute},
+};
+
+// removes `self.` within an expression so that it refers to the local variables of the already
+// deserialized fields
+pub(crate) fn strip_self(tokens: TokenStream2) -> TokenStream2
+{
+    let mut stripped = vec![];
+    let mut tokens = tokens.into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(ident) if ident == "self" && matches!(tokens.peek(), Some(TokenTree::Punct(x)) if x.as_char() == '.') => {
+                tokens.next();
+            }
+            TokenTree::Group(group) => {
+                let mut replaced = proc_macro2::Group::new(group.delimiter(), strip_self(group.stream()));
+                replaced.set_span(group.span());
+                stripped.push(TokenTree::Group(replaced));
+            }
+            token => stripped.push(token),
+        }
+    }
+
+    stripped.into_iter().collect()
+}
+
+// readable representation of an expression for error messages
+fn expr_string(tokens: &TokenStream2) -> String
+{
+    tokens
+    .to_string()
+    .replace(" . ", ".")
+    .replace(" ..= ", "..=")
+    .replace(" .. ", "..")
+    .replace(" ()", "()")
+}
+
+// checks the constraints of a field against `value` and returns an error naming the field if one
+// does not hold, assertions refer to the deserialized fields instead of `self` on deserialization
+pub(crate) fn quote_constraints(attribute: &DatatypeAttribute, value: &TokenStream2, deserialize: bool) -> TokenStream2
+{
+    quote_checks(&attribute.name, attribute.constraints.iter(), value, deserialize)
+}
+
+// checks only the constraints that do not depend on other fields
+pub(crate) fn quote_value_constraints(attribute: &DatatypeAttribute, value: &TokenStream2) -> TokenStream2
+{
+    let constraints = attribute.constraints.iter().filter(|x| !matches!(x, Constraint::Assert(_)));
+    quote_checks(&attribute.name, constraints, value, false)
+}
+
+fn quote_checks<'a>(name: &Ident, constraints: impl Iterator<Item = &'a Constraint>, value: &TokenStream2, deserialize: bool) -> TokenStream2
+{
+    let checks = constraints.map(|constraint| {
+        let (condition, message) = match constraint {
+            Constraint::Range(range) => {
+                let range = quote! { #range };
+                let range_string = expr_string(&range);
+                let message = (
+                    format!("Validation of '{}' failed: {{:?}} is not within {}", name, range_string),
+                    format!("Validation of '{}' failed: the value is not within {}", name, range_string),
+                );
+                (quote! { (#range).contains(&#value) }, message)
+            }
+            Constraint::OneOf(values) => {
+                let values = quote! { #(#values),* };
+                let values_string = expr_string(&values);
+                let message = (
+                    format!("Validation of '{}' failed: {{:?}} is not one of [{}]", name, values_string),
+                    format!("Validation of '{}' failed: the value is not one of [{}]", name, values_string),
+                );
+                (quote! { [#values].contains(&#value) }, message)
+            }
+            Constraint::Assert(expr) => {
+                let expr = quote! { #expr };
+                let expr_string = expr_string(&expr);
+                let message = (
+                    format!("Validation of '{}' failed: {{:?}} does not satisfy {}", name, expr_string),
+                    format!("Validation of '{}' failed: the value does not satisfy {}", name, expr_string),
+                );
+                let expr = match deserialize {
+                    true => strip_self(expr),
+                    false => expr,
+                };
+                (quote! { #expr }, message)
+            }
+        };
+
+        let message = quote_message(&message.0, quote! { #value }, &message.1);
+        quote! {
+            if !(#condition) {
+                return Err(#IO::Error::new(#IO::ErrorKind::InvalidData, #message));
+            }
+        }
+    });
+
+    quote! { #(#checks)* }
+}
+
+pub(crate) fn produce_validate_impl(name: &Ident, generics: &Generics, attrs: &[DatatypeAttribute]) -> TokenStream2
+{
+    if attrs.iter().all(|attribute| attribute.constraints.is_empty()) {
+        return quote! {};
+    }
+
+    // reserved and omitted fields are not part of the datatype and can only be checked on deserialization
+    let checks = attrs
+    .iter()
+    .filter(|attribute| !attribute.reserved)
+    .filter(|attribute| !attribute.calc.as_ref().is_some_and(|calc| calc.omit))
+    .map(|attribute| {
+        let field = &attribute.name;
+        quote_constraints(attribute, &quote! { self.#field }, false)
+    });
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Checks the constraints of all fields, deserialization performs the same checks
+            pub fn validate(&self) -> #IO::Result<()> {
+                #(#checks)*
+                Ok(())
+            }
+        }
+    }
+}
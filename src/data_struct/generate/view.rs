@@ -0,0 +1,301 @@
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use syn::{Lifetime, Type, Visibility};
+
+use crate::{
+    data_struct::{
+        generate::{
+            checksum_ranges,
+            deserialize::{is_field_value, quote_deserialize_attributes},
+            quote_checksum,
+            serialize::{
+                quote_serialize_decimal, quote_serialize_encoded_float, quote_serialize_integer, quote_serialize_primitive_float,
+                quote_serialize_scaled,
+            },
+            size::quote_fixed_size,
+            validate::quote_value_constraints,
+        },
+        parse::ItemStruct,
+    },
+    generate::{BYTES_EXT, IO},
+    structs::{CollectionBound, Constraint, DatatypeAttribute, DatatypeAttributeType},
+};
+
+// offset of the attribute at `index` as constant expression if all attributes in front of it are
+// statically sized, addressed attributes are always found by walking
+fn quote_static_offset(attrs: &[DatatypeAttribute], index: usize, align_to_stream: bool) -> Option<TokenStream2> {
+    match attrs.get(index).is_some_and(|attribute| attribute.address.is_some()) {
+        true => None,
+        false => quote_fixed_size(&attrs[..index], false, align_to_stream).ok(),
+    }
+}
+
+// offset of the attribute at `index`, found by reading all attributes in front of it if it is not static
+fn quote_offset(attrs: &[DatatypeAttribute], index: usize, reads: &[TokenStream2], align_to_stream: bool) -> Option<TokenStream2> {
+    if attrs.get(index).is_some_and(|attribute| attribute.address.is_some()) {
+        return None;
+    }
+
+    if let Some(offset) = quote_static_offset(attrs, index, align_to_stream) {
+        return Some(quote! { ((#offset) as usize) });
+    }
+
+    let walk = &reads[..index];
+    Some(quote! {
+        {
+            let mut cursor = #IO::Cursor::new(&self.bytes[..]);
+            let byte_stream = &mut cursor;
+            let record_start = 0u64;
+            #(#walk)*
+            byte_stream.position() as usize
+        }
+    })
+}
+
+// size of a statically sized attribute
+fn quote_size(attribute: &DatatypeAttribute) -> Option<TokenStream2> {
+    quote_fixed_size(std::slice::from_ref(attribute), false, false).ok()
+}
+
+// attributes that can be read at their offset without the values of other fields
+fn is_self_contained(attribute: &DatatypeAttribute) -> bool {
+    if attribute.constraints.iter().any(|x| matches!(x, Constraint::Assert(_))) {
+        return false;
+    }
+
+    match &attribute.ty {
+        DatatypeAttributeType::Collection(_, _, bound, _) => matches!(bound, CollectionBound::Terminator(_) | CollectionBound::Eof),
+        _ => true,
+    }
+}
+
+// writes `value` with the fixed size encoding of the attribute, None if the size depends on the value
+fn quote_write(attribute: &DatatypeAttribute, value: &TokenStream2) -> Option<TokenStream2> {
+    let endianness: &str = (&attribute.endianness).into();
+    let endianness: TokenStream2 = endianness.parse().unwrap();
+    let name = &attribute.name;
+
+    match &attribute.ty {
+        DatatypeAttributeType::PrimitiveInteger(t) => Some(quote_serialize_integer(t, &endianness, value)),
+        DatatypeAttributeType::PrimitiveFloat(t) => Some(quote_serialize_primitive_float(t, value, &endianness)),
+        DatatypeAttributeType::Scaled(_, scaling) => Some(quote_serialize_scaled(scaling, name, value, &endianness)),
+        DatatypeAttributeType::EncodedFloat(_, encoding) => Some(quote_serialize_encoded_float(encoding, name, value, &endianness)),
+        DatatypeAttributeType::Decimal(t, format) => Some(quote_serialize_decimal(t, format, name, value, &endianness)),
+        _ => None,
+    }
+}
+
+fn quote_accessor(attrs: &[DatatypeAttribute], index: usize, ty: &Type, reads: &[TokenStream2], align_to_stream: bool) -> TokenStream2 {
+    let attribute = &attrs[index];
+    let name = &attribute.name;
+
+    // fields behind variable parts or depending on other fields are found by reading all attributes in front of them
+    let read = match quote_static_offset(attrs, index, align_to_stream) {
+        Some(offset) if is_self_contained(attribute) => {
+            let read = &reads[index];
+            quote! {
+                byte_stream.set_position((#offset) as u64);
+                #read
+            }
+        }
+        _ => {
+            let walk = &reads[..=index];
+            quote! { #(#walk)* }
+        }
+    };
+
+    quote! {
+        #[allow(unused_variables, unused_mut)]
+        pub fn #name(&self) -> #IO::Result<#ty> {
+            use byteorder::BigEndian;
+            use #BYTES_EXT::ReadBytesExt;
+
+            let mut cursor = #IO::Cursor::new(self.bytes);
+            let byte_stream = &mut cursor;
+            let record_start = 0u64;
+            #read
+
+            Ok(#name)
+        }
+    }
+}
+
+// recalculates all checksums covering the attribute at `index`, including checksums covering those
+// checksums. Returns the offsets to determine before anything is written and the updates, None if
+// one of the checksums cannot be located.
+fn quote_update_checksums(attrs: &[DatatypeAttribute], index: usize, reads: &[TokenStream2], align_to_stream: bool) -> Option<(Vec<TokenStream2>, Vec<TokenStream2>)> {
+    let mut changed = vec![index];
+    let mut offsets = vec![];
+    let mut updates = vec![];
+
+    for (checksum, start, end) in checksum_ranges(attrs) {
+        if !changed.iter().any(|x| (start..end).contains(x)) {
+            continue;
+        }
+
+        let attribute = &attrs[checksum];
+        let (t, algorithm) = match &attribute.ty {
+            DatatypeAttributeType::Checksum(t, algorithm, _) => (t, algorithm),
+            _ => continue,
+        };
+        let start = quote_offset(attrs, start, reads, align_to_stream)?;
+        let end = quote_offset(attrs, end, reads, align_to_stream)?;
+        let offset = quote_offset(attrs, checksum, reads, align_to_stream)?;
+        let size = quote_size(attribute)?;
+
+        let (start_ident, end_ident, offset_ident) = (
+            format_ident!("start_{}", checksum),
+            format_ident!("end_{}", checksum),
+            format_ident!("offset_{}", checksum),
+        );
+        offsets.push(quote! {
+            let #start_ident = #start;
+            let #end_ident = #end;
+            let #offset_ident = #offset;
+            required = required.max(#offset_ident + (#size) as usize);
+        });
+
+        let ty: Ident = t.into();
+        let calculate = quote_checksum(algorithm);
+        let endianness: &str = (&attribute.endianness).into();
+        let endianness: TokenStream2 = endianness.parse().unwrap();
+        let write = quote_serialize_integer(t, &endianness, &quote! { checksum });
+
+        updates.push(quote! {
+            {
+                let checksum = {
+                    let data: &[u8] = &self.bytes[#start_ident..#end_ident];
+                    #calculate
+                } as #ty;
+                let mut cursor = #IO::Cursor::new(&mut self.bytes[..]);
+                cursor.set_position(#offset_ident as u64);
+                let writer = &mut cursor;
+                #write
+            }
+        });
+        changed.push(checksum);
+    }
+
+    Some((offsets, updates))
+}
+
+// patches a fixed size field in place and keeps the checksums covering it up to date, nothing is
+// written if the buffer is too short
+fn quote_setter(attrs: &[DatatypeAttribute], index: usize, ty: &Type, reads: &[TokenStream2], align_to_stream: bool) -> Option<TokenStream2> {
+    let attribute = &attrs[index];
+    if attribute.calc.is_some() {
+        return None;
+    }
+
+    let name = &attribute.name;
+    let setter = format_ident!("set_{}", name);
+    let write = quote_write(attribute, &quote! { value })?;
+    let offset = quote_offset(attrs, index, reads, align_to_stream)?;
+    let size = quote_size(attribute)?;
+    let (offsets, updates) = quote_update_checksums(attrs, index, reads, align_to_stream)?;
+
+    let checks = quote_value_constraints(attribute, &quote! { value });
+    let message = format!("The buffer is too short for '{}'", name);
+
+    Some(quote! {
+        #[allow(unused_variables, unused_mut)]
+        pub fn #setter(&mut self, value: #ty) -> #IO::Result<()> {
+            use byteorder::BigEndian;
+            use #BYTES_EXT::{ReadBytesExt, WriteBytesExt};
+
+            #checks
+            let offset = #offset;
+            let mut required = offset + (#size) as usize;
+            #(#offsets)*
+            if self.bytes.len() < required {
+                return Err(#IO::Error::new(#IO::ErrorKind::UnexpectedEof, #message));
+            }
+
+            {
+                let mut cursor = #IO::Cursor::new(&mut self.bytes[..]);
+                cursor.set_position(offset as u64);
+                let writer = &mut cursor;
+                #write
+            }
+            #(#updates)*
+
+            Ok(())
+        }
+    })
+}
+
+// views decoding single fields of serialized data on demand and patching fixed size fields in place
+pub(crate) fn produce_view_impl(
+    datatype_struct: &ItemStruct,
+    lifetime: Option<&Lifetime>,
+    attrs: &[DatatypeAttribute],
+    align_to_stream: bool,
+) -> TokenStream2 {
+    let name = &datatype_struct.ident;
+    let visibility: &Visibility = &datatype_struct.visibility;
+    let view = format_ident!("{}View", name);
+    let view_mut = format_ident!("{}ViewMut", name);
+    // borrowed fields are returned with the lifetime of the view
+    let lifetime = lifetime.cloned().unwrap_or_else(|| Lifetime::new("'a", proc_macro2::Span::call_site()));
+
+    let reads = quote_deserialize_attributes(attrs, align_to_stream);
+    let fields: Vec<(usize, &Type)> = attrs
+        .iter()
+        .enumerate()
+        .filter(|(_, attribute)| is_field_value(attribute))
+        .filter_map(|(index, attribute)| {
+            datatype_struct
+                .fields
+                .iter()
+                .find(|field| field.name == attribute.name)
+                .map(|field| (index, &field.ty))
+        })
+        .collect();
+
+    let accessors = fields
+        .iter()
+        .map(|(index, ty)| quote_accessor(attrs, *index, ty, &reads, align_to_stream));
+    let setters = fields
+        .iter()
+        .filter_map(|(index, ty)| quote_setter(attrs, *index, ty, &reads, align_to_stream));
+
+    let view_doc = format!("Reads single fields of a serialized [`{}`] on demand", name);
+    let view_mut_doc = format!("Patches fixed size fields of a serialized [`{}`] in place", name);
+
+    quote! {
+        #[doc = #view_doc]
+        #[derive(Debug, Clone, Copy)]
+        #visibility struct #view<#lifetime> {
+            bytes: &#lifetime [u8],
+        }
+
+        impl<#lifetime> #view<#lifetime> {
+            pub fn new(bytes: &#lifetime [u8]) -> Self {
+                #view { bytes }
+            }
+
+            pub fn as_bytes(&self) -> &#lifetime [u8] {
+                self.bytes
+            }
+
+            #(#accessors)*
+        }
+
+        #[doc = #view_mut_doc]
+        #[derive(Debug)]
+        #visibility struct #view_mut<#lifetime> {
+            bytes: &#lifetime mut [u8],
+        }
+
+        impl<#lifetime> #view_mut<#lifetime> {
+            pub fn new(bytes: &#lifetime mut [u8]) -> Self {
+                #view_mut { bytes }
+            }
+
+            pub fn view(&self) -> #view<'_> {
+                #view { bytes: self.bytes }
+            }
+
+            #(#setters)*
+        }
+    }
+}
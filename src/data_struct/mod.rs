@@ -1,5 +1,12 @@
+use crate::structs::CollectionBound;
 use crate::structs::DatatypeAttribute;
 use crate::structs::DatatypeAttributeType;
+use crate::structs::DatatypeEndianness;
+use crate::structs::Padding;
+use crate::types::checksum::ChecksumRange;
+
+use self::parse::ItemStruct;
+use self::structs::{DataFieldArg, DataStructArg, DataStructArgs};
 
 pub mod parse;
 pub mod structs;
@@ -7,13 +14,161 @@ pub mod generate;
 pub mod validate;
 
 pub(crate) fn update(attrs: &mut Vec<DatatypeAttribute>) {
-    reorder_positions_increasing(attrs)
+    reorder_positions_increasing(attrs);
+    link_count_fields(attrs);
+    link_offset_fields(attrs);
+    insert_padding(attrs);
+    move_addressed_last(attrs);
+}
+
+// marks integer fields that hold the offset of an addressed field so that they are serialized from
+// the location of the addressed field
+fn link_offset_fields(attrs: &mut [DatatypeAttribute]) {
+    let links: Vec<_> = attrs
+        .iter()
+        .filter_map(|attribute| attribute.address.as_ref().map(|x| (x.offset.clone(), attribute.name.clone())))
+        .collect();
+
+    for (field, addressed) in links {
+        if let Some(attribute) = attrs.iter_mut().find(|attribute| attribute.name == field && attribute.address.is_none()) {
+            attribute.offset_of = Some(addressed);
+        }
+    }
+}
+
+// addressed fields are placed after all other fields in the order of their declaration
+fn move_addressed_last(attrs: &mut Vec<DatatypeAttribute>) {
+    let (mut sequential, addressed): (Vec<_>, Vec<_>) = attrs
+        .drain(..)
+        .partition(|attribute| attribute.address.is_none());
+
+    sequential.extend(addressed);
+    *attrs = sequential;
+}
+
+fn padding_attribute(name: &proc_macro2::Ident, padding: Padding) -> DatatypeAttribute {
+    DatatypeAttribute {
+        name: name.clone(),
+        ty: DatatypeAttributeType::Padding(padding),
+        endianness: DatatypeEndianness::default(),
+        position: None,
+        position_span: None,
+        reserved: false,
+        length_of: None,
+        padding: vec![],
+        address: None,
+        offset_of: None,
+        calc: None,
+        constraints: vec![],
+        element_string: None,
+    }
+}
+
+// inserts the padding of a field in front of its first attribute (the length of a collection is
+// usually (de-)serialized before the collection)
+fn insert_padding(attrs: &mut Vec<DatatypeAttribute>) {
+    let mut padded: Vec<DatatypeAttribute> = Vec::with_capacity(attrs.len());
+    for mut attribute in attrs.drain(..) {
+        let padding = std::mem::take(&mut attribute.padding);
+        if !padded.iter().any(|x| x.name == attribute.name) {
+            padded.extend(padding.into_iter().map(|x| padding_attribute(&attribute.name, x)));
+        }
+
+        padded.push(attribute);
+    }
+
+    *attrs = padded;
+}
+
+// gives fields without their own endianness the endianness of the datatype, must be called before the
+// fields are turned into attributes
+pub(crate) fn apply_struct_endianness(datatype_struct: &mut ItemStruct, args: &DataStructArgs) {
+    let endianness = args.0.iter().rev().find_map(|x| match x {
+        DataStructArg::Endianness(x) => Some(x),
+        _ => None,
+    });
+
+    if let Some(endianness) = endianness {
+        for field in datatype_struct.fields.iter_mut() {
+            if !field.attrs.iter().any(|x| matches!(x, DataFieldArg::Endianness(_))) {
+                field.attrs.push(DataFieldArg::Endianness(endianness.clone()));
+            }
+        }
+    }
+}
+
+// adds the attributes that result from the arguments of the datatype itself, must be called after
+// the attributes are ordered as a magic always precedes all fields and the alignment of the
+// datatype pads its end
+pub(crate) fn apply_struct_args(attrs: &mut Vec<DatatypeAttribute>, datatype_struct: &ItemStruct, args: &DataStructArgs) {
+    for arg in &args.0 {
+        if let DataStructArg::Magic(magic) = arg {
+            attrs.insert(0, DatatypeAttribute {
+                name: datatype_struct.ident.clone(),
+                ty: DatatypeAttributeType::Magic(magic.value()),
+                endianness: DatatypeEndianness::default(),
+                position: None,
+                position_span: None,
+                reserved: false,
+                length_of: None,
+                padding: vec![],
+                address: None,
+                offset_of: None,
+                calc: None,
+                constraints: vec![],
+                element_string: None,
+            });
+        }
+    }
+
+    for arg in &args.0 {
+        if let DataStructArg::Align(align) = arg {
+            attrs.push(padding_attribute(&datatype_struct.ident, Padding::Align(*align)));
+        }
+    }
+}
+
+// indices of the attributes covered by the checksum at `index` with the end being exclusive, None if
+// a field of the range does not exist
+pub(crate) fn checksum_range(attrs: &[DatatypeAttribute], index: usize, range: &ChecksumRange) -> Option<(usize, usize)> {
+    let start = match &range.start {
+        Some(field) => attrs.iter().position(|x| x.name == *field)?,
+        None => 0,
+    };
+
+    let end = match &range.end {
+        Some(field) if range.inclusive => attrs.iter().rposition(|x| x.name == *field)? + 1,
+        Some(field) => attrs.iter().position(|x| x.name == *field)?,
+        None => index,
+    };
+
+    Some((start, end))
+}
+
+// marks integer fields that hold the length of a collection so that they are serialized from
+// the length of the collection instead of their own value
+fn link_count_fields(attrs: &mut [DatatypeAttribute]) {
+    let links: Vec<_> = attrs
+        .iter()
+        .filter_map(|attribute| match &attribute.ty {
+            DatatypeAttributeType::Collection(_, _, CollectionBound::Field(field, unit), _) => {
+                Some((field.clone(), attribute.name.clone(), unit.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+
+    for (field, collection, unit) in links {
+        if let Some(attribute) = attrs.iter_mut().find(|attribute| attribute.name == field) {
+            attribute.length_of = Some((collection, unit));
+        }
+    }
 }
 
 fn reorder_positions_increasing(attrs: &mut Vec<DatatypeAttribute>) {
     let number_of_serialization_members = attrs.iter().fold(0u32, |acc, x| acc + match x.ty {
         
-        DatatypeAttributeType::Collection(_, _) => 2,
+        DatatypeAttributeType::Collection(_, _, _, _) => 2,
         _ => 1
     });
     let mut positions: Vec<u32> = (0..number_of_serialization_members).collect();
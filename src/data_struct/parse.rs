@@ -1,5 +1,5 @@
 use proc_macro2::Span;
-use proc_macro_error::emit_warning;
+use proc_macro_error::{emit_error, emit_warning};
 use quote::ToTokens;
 use syn::ext::IdentExt;
 use syn::Attribute;
@@ -7,13 +7,21 @@ use syn::Attribute;
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
 
-use crate::parse::{get_collection_embedded_type, is_collection_type};
-use crate::structs::{DatatypeAttribute, DatatypeAttributeType, DatatypeEndianness};
-use crate::types::float::FloatType;
+use crate::parse::{get_collection_embedded_type, is_bounded_collection, is_collection_type, string_storage};
+use crate::structs::{
+    Address, Calc, CollectionBound, Constraint, DatatypeAttribute, DatatypeAttributeType, DatatypeEndianness, LengthUnit, OffsetBase, Padding, Storage,
+};
+use crate::types::checksum::{ChecksumAlgorithm, ChecksumRange};
+use crate::types::decimal::DecimalFormat;
+use crate::types::float::{FloatEncoding, FloatType};
 use crate::types::int::IntegerType;
+use crate::types::scaled::Scaling;
+use crate::types::string::{Charset, StringFormat, TextEncoding};
+use crate::types::varint::VarintEncoding;
 use syn::token::Colon;
 use syn::{
-    braced, bracketed, parenthesized, token, Error, Ident, LitInt, Result, Token, Type, Visibility,
+    braced, bracketed, parenthesized, token, Error, Expr, Generics, Ident, Lit, LitByte, LitByteStr, LitFloat, LitInt, Result, Token, Type,
+    Visibility,
 };
 
 use super::structs::{DataFieldArg, DataStructArg, DataStructArgs};
@@ -31,9 +39,50 @@ mod kw {
     syn::custom_keyword!(conditional);
     syn::custom_keyword!(version);
     syn::custom_keyword!(padding);
+    syn::custom_keyword!(varint);
+    syn::custom_keyword!(zigzag);
+    syn::custom_keyword!(string);
+    syn::custom_keyword!(pad);
+    syn::custom_keyword!(truncate);
+    syn::custom_keyword!(encoding);
+    syn::custom_keyword!(lossy);
+    syn::custom_keyword!(length_unit);
+    syn::custom_keyword!(count);
+    syn::custom_keyword!(byte_len);
+    syn::custom_keyword!(byte_len_ty);
+    syn::custom_keyword!(until);
+    syn::custom_keyword!(until_eof);
+    syn::custom_keyword!(magic);
+    syn::custom_keyword!(checksum);
+    syn::custom_keyword!(over);
+    syn::custom_keyword!(align);
+    syn::custom_keyword!(at);
+    syn::custom_keyword!(offset_ty);
+    syn::custom_keyword!(relative_to);
+    syn::custom_keyword!(calc);
+    syn::custom_keyword!(verify);
+    syn::custom_keyword!(omit);
+    syn::custom_keyword!(range);
+    syn::custom_keyword!(one_of);
+    syn::custom_keyword!(assert);
+    syn::custom_keyword!(raw);
+    syn::custom_keyword!(scale);
+    syn::custom_keyword!(offset);
+    syn::custom_keyword!(fixed);
+    syn::custom_keyword!(float);
+    syn::custom_keyword!(bcd);
+    syn::custom_keyword!(ascii_decimal);
+    syn::custom_keyword!(width);
 
     pub(crate) mod st {
         syn::custom_keyword!(fixed_size);
+        syn::custom_keyword!(magic);
+        syn::custom_keyword!(align);
+        syn::custom_keyword!(align_base);
+        syn::custom_keyword!(view);
+        syn::custom_keyword!(pod);
+        syn::custom_keyword!(codec);
+        syn::custom_keyword!(max_frame_size);
     }
 }
 
@@ -44,6 +93,7 @@ pub struct ItemStruct {
     pub visibility: Visibility,
     pub attrs: Vec<Attribute>,
     pub ident: Ident,
+    pub generics: Generics,
     pub brace_token: token::Brace,
     pub fields: Punctuated<DataField, Token![,]>,
 }
@@ -86,6 +136,135 @@ fn get_reserved(attrs: &Vec<DataFieldArg>) -> bool {
     .is_some()
 }
 
+fn get_magic(attrs: &[DataFieldArg]) -> Option<&Lit> {
+    attrs
+    .iter()
+    .find_map(|x| match x {
+        DataFieldArg::Magic(x) => Some(x),
+        _ => None,
+    })
+}
+
+fn get_checksum(attrs: &[DataFieldArg]) -> Option<&ChecksumAlgorithm> {
+    attrs
+    .iter()
+    .find_map(|x| match x {
+        DataFieldArg::Checksum(x) => Some(x),
+        _ => None,
+    })
+}
+
+fn get_checksum_range(attrs: &[DataFieldArg]) -> Option<&ChecksumRange> {
+    attrs
+    .iter()
+    .find_map(|x| match x {
+        DataFieldArg::Over(x) => Some(x),
+        _ => None,
+    })
+}
+
+fn get_padding(attrs: &[DataFieldArg]) -> Vec<Padding> {
+    let fixed = attrs.iter().filter_map(|x| match x {
+        DataFieldArg::Padding(x) => Some(Padding::Fixed(*x)),
+        _ => None,
+    });
+    let align = attrs.iter().filter_map(|x| match x {
+        DataFieldArg::Align(x) => Some(Padding::Align(*x)),
+        _ => None,
+    });
+
+    fixed.chain(align).collect()
+}
+
+fn get_at(attrs: &[DataFieldArg]) -> Option<&Ident> {
+    attrs
+    .iter()
+    .find_map(|x| match x {
+        DataFieldArg::At(x) => Some(x),
+        _ => None,
+    })
+}
+
+fn get_offset_ty(attrs: &[DataFieldArg]) -> Option<IntegerType> {
+    attrs
+    .iter()
+    .find_map(|x| match x {
+        DataFieldArg::OffsetType(x) => Some(x.clone()),
+        _ => None,
+    })
+}
+
+fn get_offset_base(attrs: &[DataFieldArg]) -> Option<OffsetBase> {
+    attrs
+    .iter()
+    .find_map(|x| match x {
+        DataFieldArg::RelativeTo(x) => Some(x.clone()),
+        _ => None,
+    })
+}
+
+fn get_calc(attrs: &[DataFieldArg]) -> Option<Calc> {
+    let expr = attrs.iter().find_map(|x| match x {
+        DataFieldArg::Calc(x) => Some(x.clone()),
+        _ => None,
+    })?;
+
+    Some(Calc {
+        expr,
+        verify: attrs.contains(&DataFieldArg::Verify),
+        omit: attrs.contains(&DataFieldArg::Omit),
+    })
+}
+
+fn get_constraints(attrs: &[DataFieldArg]) -> Vec<Constraint> {
+    attrs
+    .iter()
+    .filter_map(|x| match x {
+        DataFieldArg::Range(x) => Some(Constraint::Range(x.clone())),
+        DataFieldArg::OneOf(x) => Some(Constraint::OneOf(x.clone())),
+        DataFieldArg::Assert(x) => Some(Constraint::Assert(x.clone())),
+        _ => None,
+    })
+    .collect()
+}
+
+fn get_raw_ty(attrs: &[DataFieldArg]) -> Option<IntegerType> {
+    attrs
+    .iter()
+    .find_map(|x| match x {
+        DataFieldArg::Raw(x) => Some(x.clone()),
+        _ => None,
+    })
+}
+
+fn get_fixed(attrs: &[DataFieldArg]) -> Option<Scaling> {
+    attrs
+    .iter()
+    .find_map(|x| match x {
+        DataFieldArg::Fixed(x) => Some(x.clone()),
+        _ => None,
+    })
+}
+
+fn get_float_encoding(attrs: &[DataFieldArg]) -> Option<FloatEncoding> {
+    attrs
+    .iter()
+    .find_map(|x| match x {
+        DataFieldArg::FloatEncoding(x) => Some(x.clone()),
+        _ => None,
+    })
+}
+
+fn get_decimal(attrs: &[DataFieldArg], t: &IntegerType) -> Option<DecimalFormat> {
+    attrs
+    .iter()
+    .find_map(|x| match x {
+        DataFieldArg::Bcd(width) => Some(DecimalFormat::bcd(t, *width)),
+        DataFieldArg::AsciiDecimal(width) => Some(DecimalFormat::ascii(t, *width)),
+        _ => None,
+    })
+}
+
 fn get_position(attrs: &Vec<DataFieldArg>) -> (Option<u8>, Option<Span>)
 {
     attrs
@@ -122,80 +301,706 @@ fn get_integer_ty(attrs: &Vec<DataFieldArg>) -> IntegerType {
     .unwrap_or_default()
 }
 
-impl Into<Vec<DatatypeAttribute>> for &DataField {
-    fn into(self) -> Vec<DatatypeAttribute> {
-        let endianness = get_endianness(&self.attrs);
-        let reserved = get_reserved(&self.attrs);
-        let (position, position_span) = get_position(&self.attrs);
-
-        if is_collection_type(&self.ty).is_some() {
-            let length_ty = get_integer_ty(&self.attrs);
-            let (length_position, length_position_span) = get_length_position(&self.attrs);
-            let (collection_ty, collection_length) = get_collection_embedded_type(&self.ty);
-
-            return vec![
-                DatatypeAttribute {
-                    name: self.name.clone(),
-                    ty: DatatypeAttributeType::CollectionLength(length_ty),
-                    endianness: endianness.clone(),
-                    position: length_position,
-                    position_span: length_position_span,
-                    reserved,
-                },
-                DatatypeAttribute {
-                    name: self.name.clone(),
-                    ty: DatatypeAttributeType::Collection(collection_ty, collection_length),
-                    endianness,
-                    position,
-                    position_span,
-                    reserved,
-                },
-            ];
-        }
-        
+fn get_length_varint(attrs: &[DataFieldArg]) -> Option<VarintEncoding> {
+    attrs
+    .iter()
+    .find_map(|x| match x {
+        DataFieldArg::LengthVarint(x) => Some(x.clone()),
+        _ => None,
+    })
+}
+
+fn get_varint(attrs: &[DataFieldArg]) -> Option<VarintEncoding> {
+    attrs
+    .iter()
+    .find_map(|x| match x {
+        DataFieldArg::Varint(x) => Some(x.clone()),
+        _ => None,
+    })
+}
+
+fn get_string_format(attrs: &[DataFieldArg]) -> Option<StringFormat> {
+    let pad = attrs
+    .iter()
+    .find_map(|x| match x {
+        DataFieldArg::Pad(x) => Some(*x),
+        _ => None,
+    })
+    .unwrap_or(0);
+    let truncate = attrs.contains(&DataFieldArg::Truncate);
+
+    attrs
+    .iter()
+    .find_map(|x| match x {
+        DataFieldArg::String(StringFormat::Fixed { width, .. }) => Some(StringFormat::Fixed { width: *width, pad, truncate }),
+        DataFieldArg::String(x) => Some(x.clone()),
+        _ => None,
+    })
+}
+
+fn get_text_encoding(attrs: &[DataFieldArg]) -> Option<TextEncoding> {
+    let charset = attrs
+    .iter()
+    .find_map(|x| match x {
+        DataFieldArg::Encoding(x) => Some(x.clone()),
+        _ => None,
+    });
+    let count_units = attrs
+    .iter()
+    .find_map(|x| match x {
+        DataFieldArg::CountUnits(x) => Some(*x),
+        _ => None,
+    });
+    let lossy = attrs.contains(&DataFieldArg::Lossy);
+
+    if charset.is_none() && count_units.is_none() && !lossy {
+        return None;
+    }
+
+    Some(TextEncoding {
+        charset: charset.unwrap_or_default(),
+        lossy,
+        count_units: count_units.unwrap_or_default(),
+    })
+}
+
+// a plain identifier references a field, everything else is treated as expression
+fn get_length_reference(expr: &Expr, unit: LengthUnit) -> CollectionBound {
+    match expr {
+        Expr::Path(path) if path.path.get_ident().is_some() => {
+            CollectionBound::Field(path.path.get_ident().unwrap().clone(), unit)
+        }
+        _ => CollectionBound::Expr(expr.clone(), unit),
+    }
+}
+
+fn get_collection_bound(attrs: &[DataFieldArg]) -> Option<CollectionBound> {
+    attrs
+    .iter()
+    .find_map(|x| match x {
+        DataFieldArg::Count(x) => Some(get_length_reference(x, LengthUnit::Elements)),
+        DataFieldArg::ByteLength(x) => Some(get_length_reference(x, LengthUnit::Bytes)),
+        DataFieldArg::ByteLengthType(_, _) => Some(CollectionBound::Prefix(LengthUnit::Bytes)),
+        DataFieldArg::Until(x) => Some(CollectionBound::Terminator(x.clone())),
+        DataFieldArg::UntilEof => Some(CollectionBound::Eof),
+        _ => None,
+    })
+}
+
+fn get_byte_length_ty(attrs: &[DataFieldArg]) -> Option<(IntegerType, Option<VarintEncoding>)> {
+    attrs
+    .iter()
+    .find_map(|x| match x {
+        DataFieldArg::ByteLengthType(ty, varint) => Some((ty.clone(), varint.clone())),
+        _ => None,
+    })
+}
+
+impl DataField {
+    // type of all fields that are not collections
+    fn attribute_type(&self) -> DatatypeAttributeType {
+        let varint = get_varint(&self.attrs);
+        let string_format = get_string_format(&self.attrs);
+        let text_encoding = get_text_encoding(&self.attrs);
+
         // TODO avoid the whole conversions + string conversion and use a better approach
-        if self.ty.to_token_stream().to_string().as_str() == "String"
+        if let Some(storage) = string_storage(&self.ty)
         {
-            return vec![DatatypeAttribute {
-                name: self.name.clone(),
-                ty: DatatypeAttributeType::String,
-                endianness,
-                position,
-                position_span,
-                reserved,
-            }];
+            let (format, encoding) = self.string_encoding();
+            if storage == Storage::Borrowed && (encoding.charset != Charset::Utf8 || encoding.lossy) {
+                emit_error!(self.ty, "Borrowed strings are only supported with UTF-8 encoding without 'lossy', use Cow<str> instead");
+            }
+
+            return DatatypeAttributeType::String(format, encoding, storage);
+        }
+
+        if string_format.is_some() {
+            emit_error!(self.ty, "'string' is only supported on String, &str, Cow<str> and heapless::String<N> fields");
+        }
+
+        if self.ty.to_token_stream().to_string().as_str() == "char"
+        {
+            return DatatypeAttributeType::Char(text_encoding.unwrap_or_default());
+        }
+
+        if text_encoding.is_some() {
+            emit_error!(self.ty, "'encoding', 'lossy' and 'length_unit' are only supported on String and char fields");
         }
 
         if let Ok(x) = IntegerType::try_from(&self.ty) {
-            return vec![DatatypeAttribute {
-                name: self.name.clone(),
-                ty: DatatypeAttributeType::PrimitiveInteger(x),
-                endianness,
-                position,
-                position_span,
-                reserved,
-            }];
+            if let Some(format) = get_decimal(&self.attrs, &x) {
+                if varint.is_some() {
+                    emit_error!(self.ty, "'bcd' and 'ascii_decimal' cannot be combined with 'varint' and 'zigzag'");
+                }
+                return DatatypeAttributeType::Decimal(x, format);
+            }
+
+            return match varint {
+                Some(VarintEncoding::Zigzag) if !x.is_signed() => {
+                    emit_error!(self.ty, "'zigzag' encoding is only supported on signed integers");
+                    DatatypeAttributeType::PrimitiveInteger(x)
+                }
+                Some(VarintEncoding::Vlq) if x.is_signed() => {
+                    emit_error!(self.ty, "'vlq' encoding is only supported on unsigned integers");
+                    DatatypeAttributeType::PrimitiveInteger(x)
+                }
+                Some(encoding) => DatatypeAttributeType::VarInt(x, encoding),
+                None => DatatypeAttributeType::PrimitiveInteger(x),
+            };
         }
 
+        if varint.is_some() {
+            emit_error!(self.ty, "'varint' and 'zigzag' are only supported on integer fields");
+        }
+
+        if get_decimal(&self.attrs, &IntegerType::default()).is_some() {
+            emit_error!(self.ty, "'bcd' and 'ascii_decimal' are only supported on integer fields");
+        }
+
+        let scaling = self.scaling();
+        let float_encoding = get_float_encoding(&self.attrs);
         if let Ok(x) = FloatType::try_from(&self.ty) {
-            return vec![DatatypeAttribute {
-                name: self.name.clone(),
-                ty: DatatypeAttributeType::PrimitiveFloat(x),
-                endianness,
-                position,
-                position_span,
-                reserved,
-            }];
+            return match (scaling, float_encoding) {
+                (Some(scaling), Some(_)) => {
+                    emit_error!(self.ty, "'float' cannot be combined with scaled and fixed point fields");
+                    DatatypeAttributeType::Scaled(x, scaling)
+                }
+                (Some(scaling), None) => DatatypeAttributeType::Scaled(x, scaling),
+                (None, Some(encoding)) => DatatypeAttributeType::EncodedFloat(x, encoding),
+                (None, None) => DatatypeAttributeType::PrimitiveFloat(x),
+            };
+        }
+
+        if scaling.is_some() {
+            emit_error!(self.ty, "'raw', 'scale', 'offset' and 'fixed' are only supported on f32 and f64 fields");
+        }
+
+        if float_encoding.is_some() {
+            emit_error!(self.ty, "'float' is only supported on f32 and f64 fields");
+        }
+
+        DatatypeAttributeType::Struct(self.ty.to_token_stream().to_string())
+    }
+}
+
+impl DataField {
+    // format and encoding of a String field or of the elements of a String collection
+    fn string_encoding(&self) -> (StringFormat, TextEncoding) {
+        let format = get_string_format(&self.attrs).unwrap_or_default();
+        let encoding = get_text_encoding(&self.attrs).unwrap_or_default();
+        if let StringFormat::Fixed { width, .. } = format {
+            if width % encoding.charset.unit_size() != 0 {
+                emit_error!(self.ty, "The fixed width of {} bytes is not a multiple of the code unit size of the encoding", width);
+            }
+        }
+
+        (format, encoding)
+    }
+
+    // raw integer representation of a float field if any
+    fn scaling(&self) -> Option<Scaling> {
+        let scale = self.attrs.iter().find_map(|x| match x {
+            DataFieldArg::Scale(x) => Some(*x),
+            _ => None,
+        });
+        let offset = self.attrs.iter().find_map(|x| match x {
+            DataFieldArg::ScaleOffset(x) => Some(*x),
+            _ => None,
+        });
+
+        match (get_fixed(&self.attrs), get_raw_ty(&self.attrs)) {
+            (Some(fixed), raw) => {
+                if raw.is_some() || scale.is_some() || offset.is_some() {
+                    emit_error!(self.ty, "'fixed' cannot be combined with 'raw', 'scale' and 'offset'");
+                }
+                Some(fixed)
+            }
+            (None, Some(raw)) => Some(Scaling { raw, scale: scale.unwrap_or(1.0), offset: offset.unwrap_or(0.0) }),
+            (None, None) => {
+                if scale.is_some() || offset.is_some() {
+                    emit_error!(self.ty, "'scale' and 'offset' need the raw integer type given by 'raw'");
+                }
+                None
+            }
+        }
+    }
+
+    // bytes of the magic value as they are written to the wire, integers are written with the
+    // type and endianness of the field
+    fn magic_bytes(&self, magic: &Lit, endianness: &DatatypeEndianness) -> Vec<u8> {
+        match magic {
+            Lit::ByteStr(bytes) => {
+                let bytes = bytes.value();
+                if let Some((_, Some(length))) = is_collection_type(&self.ty) {
+                    if length as usize != bytes.len() {
+                        emit_error!(magic, "Invalid magic: the field holds {} bytes but the magic has {}", length, bytes.len());
+                    }
+                }
+
+                bytes
+            }
+            Lit::Int(value) => {
+                let t = match IntegerType::try_from(&self.ty) {
+                    Ok(t) => t,
+                    Err(_) => {
+                        emit_error!(self.ty, "Integer magic values are only supported on integer fields, use a byte string like b\"RIFF\" instead");
+                        return vec![];
+                    }
+                };
+
+                let value = value.base10_parse::<u128>().unwrap_or_else(|error| {
+                    emit_error!(magic, "{}", error);
+                    0
+                });
+                let size = t.size() as usize;
+                if size < 16 && value >> (size * 8) != 0 {
+                    emit_error!(magic, "Invalid magic: {} does not fit into the field", value);
+                }
+
+                let bytes = &value.to_be_bytes()[16 - size..];
+                match endianness {
+                    DatatypeEndianness::LittleEndian => bytes.iter().rev().cloned().collect(),
+                    DatatypeEndianness::BigEndian => bytes.to_vec(),
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl DataField {
+    fn checksum_type(&self, algorithm: &ChecksumAlgorithm) -> IntegerType {
+        let t = match IntegerType::try_from(&self.ty) {
+            Ok(t) => t,
+            Err(_) => {
+                emit_error!(self.ty, "Checksums are only supported on integer fields");
+                return IntegerType::U8;
+            }
+        };
+
+        if let Some(size) = algorithm.size() {
+            if t.size() < size {
+                emit_error!(self.ty, "The checksum needs {} bytes but the field has only {}", size, t.size());
+            }
+        }
+
+        t
+    }
+}
+
+impl DataField {
+    // location of addressed fields, the offset is either held by another field or by a separate
+    // attribute at the position of the field
+    fn address(&self) -> Option<Address> {
+        let base = get_offset_base(&self.attrs);
+        let offset = match (get_at(&self.attrs), get_offset_ty(&self.attrs)) {
+            (Some(_), Some(_)) => {
+                emit_error!(self.ty, "'at' and 'offset_ty' cannot be combined");
+                return None;
+            }
+            (Some(field), None) => field.clone(),
+            (None, Some(_)) => format_ident!("{}_offset", self.name),
+            (None, None) => {
+                if base.is_some() {
+                    emit_error!(self.ty, "'relative_to' is only supported together with 'at' or 'offset_ty'");
+                }
+                return None;
+            }
+        };
+
+        Some(Address { offset, base: base.unwrap_or(OffsetBase::Start) })
+    }
+
+    // attributes holding the data of the field
+    fn data_attributes(&self) -> Vec<DatatypeAttribute> {
+        let endianness = get_endianness(&self.attrs);
+        let reserved = get_reserved(&self.attrs);
+        let address = self.address();
+        let (position, position_span) = match address {
+            // addressed fields are moved behind all other fields anyway
+            Some(_) => (None, None),
+            None => get_position(&self.attrs),
+        };
+        let padding = match address {
+            Some(_) => vec![],
+            None => get_padding(&self.attrs),
+        };
+        let calc = get_calc(&self.attrs);
+        match &calc {
+            Some(_) if IntegerType::try_from(&self.ty).is_err() && FloatType::try_from(&self.ty).is_err() => {
+                emit_error!(self.ty, "'calc' is only supported on integer and float fields");
+            }
+            Some(_) if get_fixed(&self.attrs).is_some() || get_raw_ty(&self.attrs).is_some() => {
+                emit_error!(self.ty, "'calc' is not supported on scaled and fixed point fields");
+            }
+            Some(_) if get_float_encoding(&self.attrs).is_some() => {
+                emit_error!(self.ty, "'calc' is not supported together with 'float'");
+            }
+            None if self.attrs.contains(&DataFieldArg::Verify) || self.attrs.contains(&DataFieldArg::Omit) => {
+                emit_error!(self.ty, "'verify' and 'omit' are only supported together with 'calc'");
+            }
+            _ => {}
+        }
+        let constraints = get_constraints(&self.attrs);
+        let comparable = IntegerType::try_from(&self.ty).is_ok()
+            || FloatType::try_from(&self.ty).is_ok()
+            || self.ty.to_token_stream().to_string().as_str() == "char";
+        if !comparable && constraints.iter().any(|x| !matches!(x, Constraint::Assert(_))) {
+            emit_error!(self.ty, "'range' and 'one_of' are only supported on integer, float and char fields, use 'assert' instead");
+        }
+        if get_at(&self.attrs).is_some() && !get_padding(&self.attrs).is_empty() {
+            emit_error!(self.ty, "'align' and 'padding' are not supported on fields addressed with 'at'");
         }
 
-        vec![DatatypeAttribute {
+        let attribute = |ty: DatatypeAttributeType, position, position_span| DatatypeAttribute {
             name: self.name.clone(),
-            ty: DatatypeAttributeType::Struct(self.ty.to_token_stream().to_string()),
-            endianness,
+            constraints: match ty {
+                DatatypeAttributeType::CollectionLength(_, _, _) => vec![],
+                _ => constraints.clone(),
+            },
+            ty,
+            endianness: endianness.clone(),
             position,
             position_span,
             reserved,
-        }]
+            length_of: None,
+            padding: padding.clone(),
+            address: address.clone(),
+            offset_of: None,
+            calc: calc.clone(),
+            element_string: None,
+        };
+
+        if !constraints.is_empty() && (get_checksum(&self.attrs).is_some() || get_magic(&self.attrs).is_some()) {
+            emit_error!(self.ty, "'range', 'one_of' and 'assert' are not supported on magic and checksum fields");
+        }
+
+        let range = get_checksum_range(&self.attrs);
+        if let Some(algorithm) = get_checksum(&self.attrs) {
+            let t = self.checksum_type(algorithm);
+            let range = range.cloned().unwrap_or(ChecksumRange { start: None, end: None, inclusive: false });
+            return vec![attribute(DatatypeAttributeType::Checksum(t, algorithm.clone(), range), position, position_span)];
+        }
+
+        if range.is_some() {
+            emit_error!(self.ty, "'over' is only supported on checksum fields");
+        }
+
+        if let Some(magic) = get_magic(&self.attrs) {
+            let magic = self.magic_bytes(magic, &endianness);
+            return vec![attribute(DatatypeAttributeType::Magic(magic), position, position_span)];
+        }
+
+        if is_collection_type(&self.ty).is_none() {
+            if get_collection_bound(&self.attrs).is_some() {
+                emit_error!(self.ty, "'count', 'byte_len' and 'byte_len_ty' are only supported on collections");
+            }
+
+            return vec![attribute(self.attribute_type(), position, position_span)];
+        }
+
+        if get_varint(&self.attrs).is_some() {
+            emit_error!(self.ty, "'varint' and 'zigzag' are only supported on integer fields, use 'length_ty = varint' for the collection length");
+        }
+
+        if get_raw_ty(&self.attrs).is_some() || get_fixed(&self.attrs).is_some() || get_float_encoding(&self.attrs).is_some() {
+            emit_error!(self.ty, "'raw', 'fixed' and 'float' are only supported on f32 and f64 fields");
+        }
+
+        let bound = get_collection_bound(&self.attrs).unwrap_or(CollectionBound::Prefix(LengthUnit::Elements));
+        let (collection_ty, collection_length) = get_collection_embedded_type(&self.ty);
+        let storage = match &self.ty {
+            Type::Reference(_) => {
+                if collection_ty != "u8" {
+                    emit_error!(self.ty, "Only byte slices &[u8] can be borrowed");
+                }
+                Storage::Borrowed
+            }
+            _ if is_bounded_collection(&self.ty) => Storage::Bounded(self.ty.to_token_stream().to_string()),
+            _ => Storage::Owned,
+        };
+        let element_string = match collection_ty.as_str() {
+            "String" => Some(self.string_encoding()),
+            _ => {
+                if get_string_format(&self.attrs).is_some() || get_text_encoding(&self.attrs).is_some() {
+                    emit_error!(self.ty, "'string', 'encoding', 'lossy' and 'length_unit' are only supported on String and char fields and collections of String");
+                }
+                None
+            }
+        };
+        let mut collection = attribute(
+            DatatypeAttributeType::Collection(collection_ty, collection_length, bound.clone(), storage),
+            position,
+            position_span,
+        );
+        collection.element_string = element_string;
+
+        let unit = match bound {
+            CollectionBound::Prefix(unit) => unit,
+            _ => return vec![collection],
+        };
+
+        let length_varint = get_length_varint(&self.attrs);
+        let (length_ty, length_varint) = match (get_byte_length_ty(&self.attrs), length_varint) {
+            (Some(byte_length_ty), _) => byte_length_ty,
+            (None, Some(varint)) => (IntegerType::U64, Some(varint)),
+            (None, None) => (get_integer_ty(&self.attrs), None),
+        };
+        let (length_position, length_position_span) = get_length_position(&self.attrs);
+
+        vec![
+            attribute(
+                DatatypeAttributeType::CollectionLength(length_ty, length_varint, unit),
+                length_position,
+                length_position_span,
+            ),
+            collection,
+        ]
+    }
+}
+
+impl From<&DataField> for Vec<DatatypeAttribute> {
+    fn from(field: &DataField) -> Vec<DatatypeAttribute> {
+        let mut attrs = field.data_attributes();
+
+        // the offset takes the place of the field
+        if let Some(t) = get_offset_ty(&field.attrs) {
+            let (position, position_span) = get_position(&field.attrs);
+            attrs.insert(0, DatatypeAttribute {
+                name: field.name.clone(),
+                ty: DatatypeAttributeType::Offset(t),
+                endianness: get_endianness(&field.attrs),
+                position,
+                position_span,
+                reserved: false,
+                length_of: None,
+                padding: get_padding(&field.attrs),
+                address: None,
+                offset_of: None,
+                calc: None,
+                constraints: vec![],
+                element_string: None,
+            });
+        }
+
+        attrs
+    }
+}
+
+fn parse_byte_len(input: ParseStream) -> Result<DataFieldArg> {
+    input.parse::<kw::byte_len>()?;
+    input.parse::<Token![=]>()?;
+
+    Ok(DataFieldArg::ByteLength(input.parse::<Expr>()?))
+}
+
+fn parse_byte_len_ty(input: ParseStream) -> Result<DataFieldArg> {
+    input.parse::<kw::byte_len_ty>()?;
+    input.parse::<Token![=]>()?;
+
+    let ty: Ident = input.parse()?;
+    if is_varint_length(&ty) {
+        return Ok(DataFieldArg::ByteLengthType(IntegerType::U64, Some(VarintEncoding::try_from(ty)?)));
+    }
+
+    Ok(DataFieldArg::ByteLengthType(IntegerType::try_from(ty)?, None))
+}
+
+fn parse_count(input: ParseStream) -> Result<DataFieldArg> {
+    input.parse::<kw::count>()?;
+    input.parse::<Token![=]>()?;
+
+    Ok(DataFieldArg::Count(input.parse::<Expr>()?))
+}
+
+// alignments and paddings of zero bytes make no sense
+fn parse_alignment(input: ParseStream) -> Result<u32> {
+    input.parse::<Token![=]>()?;
+
+    let value = input.parse::<LitInt>()?;
+    match value.base10_parse::<u32>()? {
+        0 => Err(Error::new(value.span(), "Expected a value greater than 0")),
+        x => Ok(x),
+    }
+}
+
+fn parse_calc(input: ParseStream) -> Result<DataFieldArg> {
+    input.parse::<kw::calc>()?;
+    input.parse::<Token![=]>()?;
+
+    Ok(DataFieldArg::Calc(input.parse::<Expr>()?))
+}
+
+fn parse_range(input: ParseStream) -> Result<DataFieldArg> {
+    input.parse::<kw::range>()?;
+    input.parse::<Token![=]>()?;
+
+    Ok(DataFieldArg::Range(input.parse::<Expr>()?))
+}
+
+fn parse_one_of(input: ParseStream) -> Result<DataFieldArg> {
+    input.parse::<kw::one_of>()?;
+    input.parse::<Token![=]>()?;
+
+    let content;
+    bracketed!(content in input);
+    let values = Punctuated::<Expr, Token![,]>::parse_terminated(&content)?;
+
+    Ok(DataFieldArg::OneOf(values.into_iter().collect()))
+}
+
+fn parse_assert(input: ParseStream) -> Result<DataFieldArg> {
+    input.parse::<kw::assert>()?;
+    input.parse::<Token![=]>()?;
+
+    Ok(DataFieldArg::Assert(input.parse::<Expr>()?))
+}
+
+// float literal with optional sign like -40.0 or 1
+fn parse_signed_float(input: ParseStream) -> Result<f64> {
+    input.parse::<Token![=]>()?;
+
+    let negative = input.parse::<Option<Token![-]>>()?.is_some();
+    let value = match input.peek(LitInt) {
+        true => input.parse::<LitInt>()?.base10_parse::<f64>()?,
+        false => input.parse::<LitFloat>()?.base10_parse::<f64>()?,
+    };
+
+    match negative {
+        true => Ok(-value),
+        false => Ok(value),
+    }
+}
+
+fn parse_raw(input: ParseStream) -> Result<DataFieldArg> {
+    input.parse::<kw::raw>()?;
+    input.parse::<Token![=]>()?;
+
+    let ty: Ident = input.parse()?;
+    Ok(DataFieldArg::Raw(IntegerType::try_from(ty)?))
+}
+
+fn parse_scale(input: ParseStream) -> Result<DataFieldArg> {
+    let keyword = input.parse::<kw::scale>()?;
+
+    match parse_signed_float(input)? {
+        0.0 => Err(Error::new(keyword.span, "The scale must not be 0")),
+        x if !x.is_finite() => Err(Error::new(keyword.span, "The scale must be finite")),
+        x => Ok(DataFieldArg::Scale(x)),
+    }
+}
+
+fn parse_fixed(input: ParseStream) -> Result<DataFieldArg> {
+    input.parse::<kw::fixed>()?;
+    input.parse::<Token![=]>()?;
+
+    Ok(DataFieldArg::Fixed(Scaling::try_from(input.parse::<Ident>()?)?))
+}
+
+fn parse_float(input: ParseStream) -> Result<DataFieldArg> {
+    input.parse::<kw::float>()?;
+    input.parse::<Token![=]>()?;
+
+    Ok(DataFieldArg::FloatEncoding(FloatEncoding::try_from(input.parse::<Ident>()?)?))
+}
+
+// optional width like `(width = 6)` limited to `max`
+fn parse_decimal_width(input: ParseStream, max: u32) -> Result<Option<u32>> {
+    if !input.peek(token::Paren) {
+        return Ok(None);
+    }
+
+    let content;
+    parenthesized!(content in input);
+    content.parse::<kw::width>()?;
+    content.parse::<Token![=]>()?;
+
+    let value = content.parse::<LitInt>()?;
+    match value.base10_parse::<u32>()? {
+        x if x == 0 || x > max => Err(Error::new(value.span(), format!("Expected a width between 1 and {}", max))),
+        x => Ok(Some(x)),
+    }
+}
+
+fn parse_at(input: ParseStream) -> Result<DataFieldArg> {
+    input.parse::<kw::at>()?;
+    input.parse::<Token![=]>()?;
+
+    Ok(DataFieldArg::At(input.parse::<Ident>()?))
+}
+
+fn parse_offset_ty(input: ParseStream) -> Result<DataFieldArg> {
+    input.parse::<kw::offset_ty>()?;
+    input.parse::<Token![=]>()?;
+
+    let ty: Ident = input.parse()?;
+    Ok(DataFieldArg::OffsetType(IntegerType::try_from(ty)?))
+}
+
+fn parse_relative_to(input: ParseStream) -> Result<DataFieldArg> {
+    input.parse::<kw::relative_to>()?;
+    input.parse::<Token![=]>()?;
+
+    let base: Ident = input.parse()?;
+    match base.to_string().as_str() {
+        "start" => Ok(DataFieldArg::RelativeTo(OffsetBase::Start)),
+        "stream" => Ok(DataFieldArg::RelativeTo(OffsetBase::Stream)),
+        _ => Err(Error::new(base.span(), "Invalid relative_to, expected 'start' or 'stream'")),
+    }
+}
+
+fn parse_checksum(input: ParseStream) -> Result<DataFieldArg> {
+    input.parse::<kw::checksum>()?;
+    input.parse::<Token![=]>()?;
+
+    Ok(DataFieldArg::Checksum(ChecksumAlgorithm::from(input.parse::<syn::Path>()?)))
+}
+
+fn parse_over(input: ParseStream) -> Result<DataFieldArg> {
+    input.parse::<kw::over>()?;
+    input.parse::<Token![=]>()?;
+
+    let content;
+    parenthesized!(content in input);
+
+    let start = match content.peek(Ident) {
+        true => Some(content.parse::<Ident>()?),
+        false => None,
+    };
+
+    let inclusive = content.peek(Token![..=]);
+    match inclusive {
+        true => { content.parse::<Token![..=]>()?; }
+        false => { content.parse::<Token![..]>()?; }
+    }
+
+    let end = match content.is_empty() {
+        true => None,
+        false => Some(content.parse::<Ident>()?),
+    };
+
+    if inclusive && end.is_none() {
+        return Err(Error::new(content.span(), "Inclusive checksum ranges need an end field"));
+    }
+
+    Ok(DataFieldArg::Over(ChecksumRange { start, end, inclusive }))
+}
+
+fn parse_until(input: ParseStream) -> Result<DataFieldArg> {
+    input.parse::<kw::until>()?;
+    input.parse::<Token![=]>()?;
+
+    Ok(DataFieldArg::Until(input.parse::<Expr>()?))
+}
+
+fn parse_magic(input: ParseStream) -> Result<DataFieldArg> {
+    input.parse::<kw::magic>()?;
+    input.parse::<Token![=]>()?;
+
+    let magic = input.parse::<Lit>()?;
+    match magic {
+        Lit::Int(_) | Lit::ByteStr(_) => Ok(DataFieldArg::Magic(magic)),
+        _ => Err(Error::new(magic.span(), "Invalid magic, expected an integer or a byte string like b\"RIFF\"")),
     }
 }
 
@@ -209,15 +1014,107 @@ fn parse_endianness(input: ParseStream) -> Result<DataFieldArg> {
     Ok(DataFieldArg::Endianness(endianness))
 }
 
+fn is_varint_length(ty: &Ident) -> bool {
+    ty == "varint" || ty == "leb128" || ty == "vlq"
+}
+
 fn parse_length_ty(input: ParseStream) -> Result<DataFieldArg> {
     input.parse::<kw::length_ty>()?;
     input.parse::<Token![=]>()?;
 
     let ty: Ident = input.parse()?;
+    if is_varint_length(&ty) {
+        return Ok(DataFieldArg::LengthVarint(VarintEncoding::try_from(ty)?));
+    }
+
     let ty = IntegerType::try_from(ty)?;
 
     Ok(DataFieldArg::LengthType(ty))
 }
+
+fn parse_string(input: ParseStream) -> Result<DataFieldArg> {
+    input.parse::<kw::string>()?;
+    input.parse::<Token![=]>()?;
+
+    let format: Ident = input.parse()?;
+    let content;
+    match format.to_string().as_str() {
+        "terminated" => {
+            if !input.peek(token::Paren) {
+                return Ok(DataFieldArg::String(StringFormat::Terminated(0)));
+            }
+
+            parenthesized!(content in input);
+            let terminator = content.parse::<LitInt>()?.base10_parse::<u8>()?;
+
+            Ok(DataFieldArg::String(StringFormat::Terminated(terminator)))
+        }
+        "prefixed" => {
+            parenthesized!(content in input);
+            let ty: Ident = content.parse()?;
+            if is_varint_length(&ty) {
+                return Ok(DataFieldArg::String(StringFormat::Prefixed(IntegerType::U64, Some(VarintEncoding::try_from(ty)?))));
+            }
+
+            Ok(DataFieldArg::String(StringFormat::Prefixed(IntegerType::try_from(ty)?, None)))
+        }
+        "fixed" => {
+            parenthesized!(content in input);
+            let width = content.parse::<LitInt>()?.base10_parse::<usize>()?;
+
+            Ok(DataFieldArg::String(StringFormat::Fixed { width, pad: 0, truncate: false }))
+        }
+        _ => Err(Error::new(format.span(), "Invalid string format, expected 'terminated', 'prefixed' or 'fixed'")),
+    }
+}
+
+fn parse_encoding(input: ParseStream) -> Result<DataFieldArg> {
+    input.parse::<kw::encoding>()?;
+    input.parse::<Token![=]>()?;
+
+    let charset: Ident = input.parse()?;
+    let charset = Charset::try_from(charset)?;
+
+    Ok(DataFieldArg::Encoding(charset))
+}
+
+fn parse_length_unit(input: ParseStream) -> Result<DataFieldArg> {
+    input.parse::<kw::length_unit>()?;
+    input.parse::<Token![=]>()?;
+
+    let unit: Ident = input.parse()?;
+    match unit.to_string().as_str() {
+        "bytes" => Ok(DataFieldArg::CountUnits(false)),
+        "code_units" => Ok(DataFieldArg::CountUnits(true)),
+        _ => Err(Error::new(unit.span(), "Invalid length unit, expected 'bytes' or 'code_units'")),
+    }
+}
+
+fn parse_pad(input: ParseStream) -> Result<DataFieldArg> {
+    input.parse::<kw::pad>()?;
+    input.parse::<Token![=]>()?;
+
+    if input.peek(LitByte) {
+        return Ok(DataFieldArg::Pad(input.parse::<LitByte>()?.value()));
+    }
+
+    let value = input.parse::<LitInt>()?.base10_parse::<u8>()?;
+
+    Ok(DataFieldArg::Pad(value))
+}
+
+fn parse_varint(input: ParseStream) -> Result<DataFieldArg> {
+    input.parse::<kw::varint>()?;
+    if !input.peek(Token![=]) {
+        return Ok(DataFieldArg::Varint(VarintEncoding::Leb128));
+    }
+
+    input.parse::<Token![=]>()?;
+    let encoding: Ident = input.parse()?;
+    let encoding = VarintEncoding::try_from(encoding)?;
+
+    Ok(DataFieldArg::Varint(encoding))
+}
 fn parse_length_position(input: ParseStream) -> Result<DataFieldArg> {
     input.parse::<kw::length_position>()?;
     input.parse::<Token![=]>()?;
@@ -305,9 +1202,151 @@ fn parse_muu(input: ParseStream) -> Result<DataFieldArg> {
         );
     }
 
+    if lookahead.peek(kw::varint) {
+        return parse_varint(input);
+    }
+
+    if lookahead.peek(kw::zigzag) {
+        input.parse::<kw::zigzag>()?;
+        return Ok(DataFieldArg::Varint(VarintEncoding::Zigzag));
+    }
+
+    if lookahead.peek(kw::string) {
+        return parse_string(input);
+    }
+
+    if lookahead.peek(kw::pad) {
+        return parse_pad(input);
+    }
+
+    if lookahead.peek(kw::truncate) {
+        input.parse::<kw::truncate>()?;
+        return Ok(DataFieldArg::Truncate);
+    }
+
+    if lookahead.peek(kw::encoding) {
+        return parse_encoding(input);
+    }
+
+    if lookahead.peek(kw::lossy) {
+        input.parse::<kw::lossy>()?;
+        return Ok(DataFieldArg::Lossy);
+    }
+
+    if lookahead.peek(kw::length_unit) {
+        return parse_length_unit(input);
+    }
+
+    if lookahead.peek(kw::count) {
+        return parse_count(input);
+    }
+
+    if lookahead.peek(kw::byte_len) {
+        return parse_byte_len(input);
+    }
+
+    if lookahead.peek(kw::byte_len_ty) {
+        return parse_byte_len_ty(input);
+    }
+
+    if lookahead.peek(kw::until) {
+        return parse_until(input);
+    }
+
+    if lookahead.peek(kw::until_eof) {
+        input.parse::<kw::until_eof>()?;
+        return Ok(DataFieldArg::UntilEof);
+    }
+
+    if lookahead.peek(kw::calc) {
+        return parse_calc(input);
+    }
+
+    if lookahead.peek(kw::verify) {
+        input.parse::<kw::verify>()?;
+        return Ok(DataFieldArg::Verify);
+    }
+
+    if lookahead.peek(kw::omit) {
+        input.parse::<kw::omit>()?;
+        return Ok(DataFieldArg::Omit);
+    }
+
+    if lookahead.peek(kw::range) {
+        return parse_range(input);
+    }
+
+    if lookahead.peek(kw::one_of) {
+        return parse_one_of(input);
+    }
+
+    if lookahead.peek(kw::assert) {
+        return parse_assert(input);
+    }
+
+    if lookahead.peek(kw::raw) {
+        return parse_raw(input);
+    }
+
+    if lookahead.peek(kw::scale) {
+        return parse_scale(input);
+    }
+
+    if lookahead.peek(kw::offset) {
+        input.parse::<kw::offset>()?;
+        return Ok(DataFieldArg::ScaleOffset(parse_signed_float(input)?));
+    }
+
+    if lookahead.peek(kw::fixed) {
+        return parse_fixed(input);
+    }
+
+    if lookahead.peek(kw::float) {
+        return parse_float(input);
+    }
+
+    if lookahead.peek(kw::bcd) {
+        input.parse::<kw::bcd>()?;
+        return Ok(DataFieldArg::Bcd(parse_decimal_width(input, DecimalFormat::MAX_BCD_WIDTH)?));
+    }
+
+    if lookahead.peek(kw::ascii_decimal) {
+        input.parse::<kw::ascii_decimal>()?;
+        return Ok(DataFieldArg::AsciiDecimal(parse_decimal_width(input, DecimalFormat::MAX_ASCII_WIDTH)?));
+    }
+
+    if lookahead.peek(kw::at) {
+        return parse_at(input);
+    }
+
+    if lookahead.peek(kw::offset_ty) {
+        return parse_offset_ty(input);
+    }
+
+    if lookahead.peek(kw::relative_to) {
+        return parse_relative_to(input);
+    }
+
+    if lookahead.peek(kw::checksum) {
+        return parse_checksum(input);
+    }
+
+    if lookahead.peek(kw::over) {
+        return parse_over(input);
+    }
+
+    if lookahead.peek(kw::magic) {
+        return parse_magic(input);
+    }
+
     if lookahead.peek(kw::padding) {
         input.parse::<kw::padding>()?;
-        return Ok(DataFieldArg::Padding)
+        return Ok(DataFieldArg::Padding(parse_alignment(input)?));
+    }
+
+    if lookahead.peek(kw::align) {
+        input.parse::<kw::align>()?;
+        return Ok(DataFieldArg::Align(parse_alignment(input)?));
     }
 
     Err(Error::new(input.span(), "Unknown attribute"))
@@ -371,22 +1410,142 @@ impl Parse for ItemStruct {
 
         let visibility = input.parse::<Visibility>()?;
         let _struct_token: Token![struct] = input.parse()?;
+        let ident = input.parse()?;
+        let mut generics = input.parse::<Generics>()?;
+        generics.where_clause = input.parse()?;
 
         Ok(ItemStruct {
             visibility,
             attrs,
-            ident: input.parse()?,
+            ident,
+            generics,
             brace_token: braced!(content in input),
             fields: content.parse_terminated(parse_named, Token![,])?,
         })
     }
 }
 
+fn parse_fixed_size(input: ParseStream) -> Result<DataStructArg> {
+    input.parse::<kw::st::fixed_size>()?;
+    if !input.peek(Token![=]) {
+        return Ok(DataStructArg::FixedSize(None));
+    }
+
+    input.parse::<Token![=]>()?;
+    let value = input.parse::<LitInt>()?;
+    if value.base10_parse::<usize>()? % 8 != 0 {
+        return Err(Error::new(value.span(), "fixed_size is given in bits and must be a multiple of 8"));
+    }
+
+    Ok(DataStructArg::FixedSize(Some(value)))
+}
+
+fn parse_codec(input: ParseStream) -> Result<DataStructArg> {
+    input.parse::<kw::st::codec>()?;
+    let mut length = None;
+    let mut max_frame_size = None;
+    if !input.peek(token::Paren) {
+        return Ok(DataStructArg::Codec { length, max_frame_size });
+    }
+
+    let content;
+    parenthesized!(content in input);
+    while !content.is_empty() {
+        let lookahead = content.lookahead1();
+        if lookahead.peek(kw::length_ty) {
+            content.parse::<kw::length_ty>()?;
+            content.parse::<Token![=]>()?;
+            length = Some(IntegerType::try_from(content.parse::<Ident>()?)?);
+        } else if lookahead.peek(kw::st::max_frame_size) {
+            content.parse::<kw::st::max_frame_size>()?;
+            content.parse::<Token![=]>()?;
+            max_frame_size = Some(content.parse::<LitInt>()?.base10_parse::<usize>()?);
+        } else {
+            return Err(lookahead.error());
+        }
+
+        if !content.is_empty() {
+            content.parse::<Token![,]>()?;
+        }
+    }
+
+    Ok(DataStructArg::Codec { length, max_frame_size })
+}
+
+fn parse_struct_magic(input: ParseStream) -> Result<DataStructArg> {
+    input.parse::<kw::st::magic>()?;
+    input.parse::<Token![=]>()?;
+    let value = input.parse::<LitByteStr>()?;
+
+    Ok(DataStructArg::Magic(value))
+}
+
+fn parse_struct_endianness(input: ParseStream) -> Result<DataStructArg> {
+    input.parse::<kw::endianness>()?;
+    input.parse::<Token![=]>()?;
+
+    let endianness: Ident = input.parse()?;
+    Ok(DataStructArg::Endianness(DatatypeEndianness::try_from(endianness)?))
+}
+
+fn parse_align_base(input: ParseStream) -> Result<DataStructArg> {
+    input.parse::<kw::st::align_base>()?;
+    input.parse::<Token![=]>()?;
+
+    let base: Ident = input.parse()?;
+    match base.to_string().as_str() {
+        "record" => Ok(DataStructArg::AlignToStream(false)),
+        "stream" => Ok(DataStructArg::AlignToStream(true)),
+        _ => Err(Error::new(base.span(), "Invalid align_base, expected 'record' or 'stream'")),
+    }
+}
+
 impl Parse for DataStructArg {
     fn parse(input: ParseStream) -> Result<Self> {
         let lookahead = input.lookahead1();
+        if lookahead.peek(kw::serialize) {
+            input.parse::<kw::serialize>()?;
+            return Ok(DataStructArg::Serialize);
+        }
+
+        if lookahead.peek(kw::deserialize) {
+            input.parse::<kw::deserialize>()?;
+            return Ok(DataStructArg::Deserialize);
+        }
+
+        if lookahead.peek(kw::endianness) {
+            return parse_struct_endianness(input);
+        }
+
         if lookahead.peek(kw::st::fixed_size) {
-            return parse_endianness(&input);
+            return parse_fixed_size(input);
+        }
+
+        if lookahead.peek(kw::st::magic) {
+            return parse_struct_magic(input);
+        }
+
+        if lookahead.peek(kw::st::align) {
+            input.parse::<kw::st::align>()?;
+            return Ok(DataStructArg::Align(parse_alignment(input)?));
+        }
+
+        if lookahead.peek(kw::st::align_base) {
+            return parse_align_base(input);
+        }
+
+        if lookahead.peek(kw::st::view) {
+            input.parse::<kw::st::view>()?;
+            return Ok(DataStructArg::View);
+        }
+
+        if lookahead.peek(kw::st::codec) {
+            return parse_codec(input);
+        }
+
+        if lookahead.peek(kw::st::pod) {
+            input.parse::<kw::st::pod>()?;
+            return Ok(DataStructArg::Pod);
         }
 
         Err(Error::new(input.span(), "Unknown attribute"))
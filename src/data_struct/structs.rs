@@ -1,11 +1,27 @@
-use syn::{Ident, LitInt};
+use syn::{Expr, Ident, Lit, LitByteStr, LitInt};
 
-use crate::{structs::DatatypeEndianness, types::int::IntegerType};
+use crate::{structs::{DatatypeEndianness, OffsetBase}, types::{checksum::{ChecksumAlgorithm, ChecksumRange}, float::FloatEncoding, int::IntegerType, scaled::Scaling, string::{Charset, StringFormat}, varint::VarintEncoding}};
 
 #[derive(PartialEq, Debug, Clone)]
 
 pub(crate) enum DataStructArg {
-    FixedSize(LitInt)
+    // only generates the listed directions, both if neither is given
+    Serialize,
+    Deserialize,
+    // default endianness of all fields without their own
+    Endianness(DatatypeEndianness),
+    // statically sized datatype, optionally with the expected size
+    FixedSize(Option<LitInt>),
+    Magic(LitByteStr),
+    Align(u32),
+    // alignment is relative to the position in the stream instead of the start of the datatype
+    AlignToStream(bool),
+    // generates lazy views decoding single fields of serialized data
+    View,
+    // repr(C, packed) layout (de-)serialized with a single copy of the whole struct
+    Pod,
+    // tokio-util codec, frames are optionally prefixed with their length
+    Codec { length: Option<IntegerType>, max_frame_size: Option<usize> },
 }
 
 pub(crate) struct DataStructArgs(pub(crate) Vec<DataStructArg>);
@@ -14,12 +30,47 @@ pub(crate) struct DataStructArgs(pub(crate) Vec<DataStructArg>);
 pub(crate) enum DataFieldArg {
     Endianness(DatatypeEndianness),
     LengthType(IntegerType),
+    LengthVarint(VarintEncoding),
+    Varint(VarintEncoding),
+    String(StringFormat),
+    Pad(u8),
+    Truncate,
+    Encoding(Charset),
+    Lossy,
+    CountUnits(bool),
+    Count(Expr),
+    ByteLengthType(IntegerType, Option<VarintEncoding>),
+    ByteLength(Expr),
+    Until(Expr),
+    UntilEof,
+    Magic(Lit),
+    Checksum(ChecksumAlgorithm),
+    Over(ChecksumRange),
+    At(Ident),
+    OffsetType(IntegerType),
+    RelativeTo(OffsetBase),
+    Calc(Expr),
+    Verify,
+    Omit,
+    Range(Expr),
+    OneOf(Vec<Expr>),
+    Assert(Expr),
+    Raw(IntegerType),
+    Scale(f64),
+    ScaleOffset(f64),
+    Fixed(Scaling),
+    FloatEncoding(FloatEncoding),
+    // width in bytes
+    Bcd(Option<u32>),
+    // width in digits
+    AsciiDecimal(Option<u32>),
     Position(LitInt),
     LengthPosition(LitInt),
     SerializeFunction(Ident),
     //DeserializeFunction(Ident),
     Ignore,
-    Padding,
+    Padding(u32),
+    Align(u32),
     //Coditional(Ident),
     //Version(Ident),
     Reserved,
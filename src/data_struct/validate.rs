@@ -2,7 +2,9 @@ use std::collections::HashMap;
 
 use proc_macro_error::emit_error;
 
-use crate::structs::DatatypeAttribute;
+use crate::data_struct::checksum_range;
+use crate::structs::{CollectionBound, DatatypeAttribute, DatatypeAttributeType};
+use proc_macro2::Ident;
 
 pub(crate) fn validate(attrs:& Vec<DatatypeAttribute>)
 {
@@ -52,4 +54,149 @@ fn check_for_double_indices(attrs: &Vec<DatatypeAttribute>) {
             );
         }
     }
+}
+
+/// Checks constraints on the order of the attributes like fields that are referenced by other
+/// fields. Must be called after the attributes are ordered.
+pub(crate) fn validate_order(attrs: &[DatatypeAttribute])
+{
+    check_count_fields(attrs);
+    check_until_eof_last(attrs);
+    check_checksums(attrs);
+    check_offset_fields(attrs);
+}
+
+fn check_offset_fields(attrs: &[DatatypeAttribute]) {
+    let mut used: Vec<&Ident> = vec![];
+
+    for (index, attribute) in attrs.iter().enumerate() {
+        let address = match &attribute.address {
+            Some(address) => address,
+            None => continue,
+        };
+
+        // the implicit offset attribute is always valid
+        if attrs.iter().any(|x| x.name == attribute.name && matches!(x.ty, DatatypeAttributeType::Offset(_))) {
+            continue;
+        }
+
+        // collections consist of two addressed attributes
+        if index > 0 && attrs[index - 1].name == attribute.name && attrs[index - 1].address.is_some() {
+            continue;
+        }
+
+        let field = &address.offset;
+        let offset = attrs.iter().find(|x| x.name == *field && x.address.is_none());
+        match offset {
+            Some(offset) if matches!(offset.ty, DatatypeAttributeType::PrimitiveInteger(_)) && !offset.reserved => {}
+            _ => emit_error!(
+                field.span(),
+                "Invalid offset: '{}' is not an integer field of the datatype",
+                field
+            ),
+        }
+
+        if used.contains(&field) {
+            emit_error!(
+                field.span(),
+                "Invalid offset: '{}' is already used as offset of another field",
+                field
+            );
+        }
+        used.push(field);
+    }
+}
+
+fn check_checksums(attrs: &[DatatypeAttribute]) {
+    for (index, attribute) in attrs.iter().enumerate() {
+        let range = match &attribute.ty {
+            DatatypeAttributeType::Checksum(_, _, range) => range,
+            _ => continue,
+        };
+
+        let (start, end) = match checksum_range(attrs, index, range) {
+            Some(x) => x,
+            None => {
+                emit_error!(
+                    attribute.name.span(),
+                    "Invalid checksum range: '{}' references a field that is not part of the datatype",
+                    attribute.name
+                );
+                continue;
+            }
+        };
+
+        if start >= end {
+            emit_error!(attribute.name.span(), "Invalid checksum range: '{}' covers no fields", attribute.name);
+            continue;
+        }
+
+        if attrs[start..end].iter().any(|x| x.address.is_some()) {
+            emit_error!(
+                attribute.name.span(),
+                "Invalid checksum range: '{}' covers fields that are addressed by an offset",
+                attribute.name
+            );
+            continue;
+        }
+
+        if end > index {
+            emit_error!(
+                attribute.name.span(),
+                "Invalid checksum range: '{}' must be (de-)serialized after the fields it covers",
+                attribute.name
+            );
+            continue;
+        }
+
+    }
+}
+
+fn check_until_eof_last(attrs: &[DatatypeAttribute]) {
+    let last = attrs.len().saturating_sub(1);
+    for (index, attribute) in attrs.iter().enumerate() {
+        if index != last && matches!(attribute.ty, DatatypeAttributeType::Collection(_, _, CollectionBound::Eof, _)) {
+            emit_error!(
+                attribute.name.span(),
+                "Invalid until_eof: '{}' consumes the remaining input and must be the last field of the datatype",
+                attribute.name
+            );
+        }
+    }
+}
+
+fn check_count_fields(attrs: &[DatatypeAttribute]) {
+    for (index, attribute) in attrs.iter().enumerate() {
+        let field = match &attribute.ty {
+            DatatypeAttributeType::Collection(_, _, CollectionBound::Field(field, _), _) => field,
+            _ => continue,
+        };
+
+        let count = attrs.iter().position(|x| x.name == *field && matches!(
+            x.ty,
+            DatatypeAttributeType::PrimitiveInteger(_) | DatatypeAttributeType::VarInt(_, _) | DatatypeAttributeType::Decimal(_, _)
+        ));
+
+        match count {
+            None => emit_error!(
+                field.span(),
+                "Invalid length: '{}' is not an integer field of the datatype",
+                field
+            ),
+            Some(count) if count > index => emit_error!(
+                field.span(),
+                "Invalid length: '{}' must be (de-)serialized before '{}'",
+                field, attribute.name
+            ),
+            Some(_) => {}
+        }
+
+        if attrs[index + 1..].iter().any(|x| matches!(&x.ty, DatatypeAttributeType::Collection(_, _, CollectionBound::Field(other, _), _) if other == field)) {
+            emit_error!(
+                field.span(),
+                "Invalid length: '{}' is already used as length of another collection",
+                field
+            );
+        }
+    }
 }
\ No newline at end of file
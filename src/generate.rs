@@ -0,0 +1,230 @@
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use quote::ToTokens;
+use syn::{Generics, Lifetime};
+
+// paths of std items in the generated code. With the `no_std` feature io is provided by the runtime
+// next to the datatype traits, allocating types come from alloc and everything else from core
+#[derive(Clone, Copy)]
+pub(crate) enum StdPath {
+    // std::io or crate::datatypes::io
+    Io,
+    // std or core
+    Core,
+    // std or alloc
+    Alloc,
+    Vec,
+    String,
+    VecMacro,
+    // writer passed to Serialize::serialize, std::io::BufWriter<std::fs::File> or crate::datatypes::io::Writer
+    Writer,
+    // home of ReadBytesExt and WriteBytesExt, byteorder or crate::datatypes::io
+    BytesExt,
+}
+
+pub(crate) const IO: StdPath = StdPath::Io;
+pub(crate) const CORE: StdPath = StdPath::Core;
+pub(crate) const ALLOC: StdPath = StdPath::Alloc;
+pub(crate) const VEC: StdPath = StdPath::Vec;
+pub(crate) const STRING: StdPath = StdPath::String;
+pub(crate) const VEC_MACRO: StdPath = StdPath::VecMacro;
+pub(crate) const WRITER: StdPath = StdPath::Writer;
+pub(crate) const BYTES_EXT: StdPath = StdPath::BytesExt;
+
+impl ToTokens for StdPath {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let path = match (self, cfg!(feature = "no_std")) {
+            (StdPath::Io, false) => quote! { std::io },
+            (StdPath::Io, true) => quote! { crate::datatypes::io },
+            (StdPath::Core, false) | (StdPath::Alloc, false) => quote! { std },
+            (StdPath::Core, true) => quote! { core },
+            (StdPath::Alloc, true) => quote! { alloc },
+            (StdPath::Vec, false) => quote! { Vec },
+            (StdPath::Vec, true) => quote! { alloc::vec::Vec },
+            (StdPath::String, false) => quote! { String },
+            (StdPath::String, true) => quote! { alloc::string::String },
+            (StdPath::VecMacro, false) => quote! { vec },
+            (StdPath::VecMacro, true) => quote! { alloc::vec },
+            (StdPath::Writer, false) => quote! { std::io::BufWriter<std::fs::File> },
+            (StdPath::Writer, true) => quote! { crate::datatypes::io::Writer },
+            (StdPath::BytesExt, false) => quote! { byteorder },
+            (StdPath::BytesExt, true) => quote! { crate::datatypes::io },
+        };
+        tokens.extend(path);
+    }
+}
+
+// message of an error caused by a value, formatting the values allocates and is therefore left out with
+// the `no_std` feature where the static message is used instead
+pub(crate) fn quote_message(message: &str, args: TokenStream2, static_message: &str) -> TokenStream2 {
+    match cfg!(feature = "no_std") {
+        true => quote! { #static_message },
+        false => quote! { format!(#message, #args) },
+    }
+}
+
+// helpers working on byte slices for the generated directions, the datatype needs an inherent `write_to`
+// writing to any seekable writer and an inherent `read_from` if it borrows from the input with `lifetime`
+pub(crate) fn produce_bytes_impl(name: &Ident, generics: &Generics, lifetime: Option<&Lifetime>, serialize: bool, deserialize: bool) -> TokenStream2 {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (bytes, read) = match lifetime {
+        Some(lifetime) => (quote! { &#lifetime [u8] }, quote! { Self::read_from(&mut byte_stream)? }),
+        None => (quote! { &[u8] }, quote! { <Self as crate::datatypes::Deserialize>::deserialize(&mut byte_stream)? }),
+    };
+
+    let cannot_serialize = format!("'{}' cannot be serialized", name);
+    let write = match serialize {
+        true => quote! {
+            /// Serializes the datatype into `buffer` and returns the number of bytes written. The buffer is
+            /// written through a Cursor so that offsets and alignments work the same as with any other writer
+            pub fn serialize_into(&self, buffer: &mut [u8]) -> #IO::Result<usize> {
+                let mut writer = #IO::Cursor::new(buffer);
+                self.write_to(&mut writer)?;
+
+                Ok(writer.position() as usize)
+            }
+
+            /// Serializes the datatype into a buffer allocated with the size of the datatype
+            ///
+            /// # Panics
+            ///
+            /// If the datatype cannot be serialized, e.g. because a value violates its constraints or a
+            /// collection is too long for its length field. Use `try_to_bytes` to handle these errors.
+            pub fn to_bytes(&self) -> #VEC<u8> {
+                self.try_to_bytes().expect(#cannot_serialize)
+            }
+
+            /// Serializes the datatype into a buffer allocated with the size of the datatype
+            pub fn try_to_bytes(&self) -> #IO::Result<#VEC<u8>> {
+                let size = crate::datatypes::Serialize::size(self) as usize;
+                let mut writer = #IO::Cursor::new(#VEC::with_capacity(size));
+                self.write_to(&mut writer)?;
+
+                Ok(writer.into_inner())
+            }
+        },
+        false => quote! {},
+    };
+
+    let read = match deserialize {
+        true => quote! {
+            /// Deserializes the datatype from the start of `bytes` and returns it with the number of bytes consumed
+            pub fn from_bytes(bytes: #bytes) -> #IO::Result<(Self, usize)> {
+                let mut byte_stream = #IO::Cursor::new(bytes);
+                let value = #read;
+
+                Ok((value, byte_stream.position() as usize))
+            }
+        },
+        false => quote! {},
+    };
+
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #write
+
+            #read
+        }
+    }
+}
+
+// tokio counterparts of the blocking (de-)serialization. Datatypes are (de-)serialized through an in-memory
+// buffer with the blocking implementation so that nested datatypes and collections behave exactly the same.
+// Only the bytes of the datatype are consumed from the stream, statically sized datatypes with a single
+// read. Other datatypes need a buffered reader, whatever it holds is appended to the buffer and decoded,
+// but only the bytes belonging to the datatype are consumed. The buffer therefore only grows with bytes
+// that actually arrived and is decoded once per fill of the reader instead of once per byte. Datatypes
+// reading until the end of their input read the whole stream.
+pub(crate) fn produce_async_impl(name: &Ident, generics: &Generics, lifetime: Option<&Lifetime>, fixed_size: bool, until_eof: bool, serialize: bool, deserialize: bool) -> TokenStream2 {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let read = match (fixed_size, until_eof) {
+        (true, _) => quote! {
+            let mut buffer = [0u8; #name::SIZE];
+            tokio::io::AsyncReadExt::read_exact(reader, &mut buffer).await?;
+            Ok(Self::from_bytes(&buffer)?.0)
+        },
+        (false, true) => quote! {
+            let mut buffer = #VEC::new();
+            tokio::io::AsyncReadExt::read_to_end(reader, &mut buffer).await?;
+            Ok(Self::from_bytes(&buffer)?.0)
+        },
+        (false, false) => quote! {
+            let mut buffer = #VEC::new();
+            loop {
+                let start = buffer.len();
+                buffer.extend_from_slice(tokio::io::AsyncBufReadExt::fill_buf(reader).await?);
+                let end = buffer.len();
+
+                match Self::decode(&buffer)? {
+                    crate::datatypes::Decoded::Complete(value, consumed) => {
+                        tokio::io::AsyncBufReadExt::consume(reader, consumed.saturating_sub(start));
+                        return Ok(value);
+                    }
+                    crate::datatypes::Decoded::Incomplete { .. } if end == start => {
+                        return Err(#IO::Error::from(#IO::ErrorKind::UnexpectedEof));
+                    }
+                    crate::datatypes::Decoded::Incomplete { .. } => tokio::io::AsyncBufReadExt::consume(reader, end - start),
+                }
+            }
+        },
+    };
+    let reader = match (fixed_size, until_eof) {
+        (false, false) => quote! { tokio::io::AsyncBufRead },
+        _ => quote! { tokio::io::AsyncRead },
+    };
+
+    // datatypes borrowing from the input cannot outlive the buffer
+    let deserialize = match (deserialize, lifetime) {
+        (false, _) | (true, Some(_)) => quote! {},
+        (true, None) => quote! {
+            /// Deserializes the datatype from an async reader, only the bytes of the datatype are consumed
+            pub async fn deserialize_async<R: #reader + Unpin>(reader: &mut R) -> #IO::Result<Self> {
+                #read
+            }
+        },
+    };
+
+    let serialize = match serialize {
+        true => quote! {
+            /// Serializes the datatype into an async writer
+            pub async fn serialize_async<W: tokio::io::AsyncWrite + Unpin>(&self, writer: &mut W) -> #IO::Result<()> {
+                let bytes = self.try_to_bytes()?;
+                tokio::io::AsyncWriteExt::write_all(writer, &bytes).await
+            }
+        },
+        false => quote! {},
+    };
+
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #serialize
+
+            #deserialize
+        }
+    }
+}
+
+// resumable decoding of statically sized datatypes, the missing amount of bytes is always known
+pub(crate) fn produce_fixed_decode_impl(name: &Ident, generics: &Generics, lifetime: Option<&Lifetime>) -> TokenStream2 {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let bytes = match lifetime {
+        Some(lifetime) => quote! { &#lifetime [u8] },
+        None => quote! { &[u8] },
+    };
+
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Decodes the datatype from the start of `bytes` without consuming anything if the input is
+            /// incomplete, `needed` is the amount of missing bytes
+            pub fn decode(bytes: #bytes) -> #IO::Result<crate::datatypes::Decoded<Self>> {
+                if bytes.len() < #name::SIZE {
+                    return Ok(crate::datatypes::Decoded::Incomplete { needed: Some(#name::SIZE - bytes.len()) });
+                }
+
+                let (value, consumed) = Self::from_bytes(bytes)?;
+                Ok(crate::datatypes::Decoded::Complete(value, consumed))
+            }
+        }
+    }
+}
+
@@ -8,10 +8,14 @@ mod parse;
 mod structs;
 mod data_enum;
 mod data_struct;
+mod generate;
 mod types;
 
+#[cfg(all(feature = "async", feature = "no_std"))]
+compile_error!("The 'async' feature requires std and cannot be combined with 'no_std'");
+
 use data_enum::structs::DataEnumArgs;
-use data_struct::{parse::ItemStruct, validate::validate, update};
+use data_struct::{apply_struct_args, apply_struct_endianness, parse::ItemStruct, structs::DataStructArgs, validate::{validate, validate_order}, update};
 use proc_macro::TokenStream;
 
 use proc_macro_error::proc_macro_error;
@@ -27,7 +31,11 @@ use syn::{DeriveInput, parse_macro_input};
 /// Simple struct where all attributes shall be serialized and deserialized:
 /// Per default all member within a struct are then (de-)serialized.
 /// 
-/// ```rust
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
 /// #[datatype]
 /// pub struct TimedMessage
 /// {
@@ -37,13 +45,57 @@ use syn::{DeriveInput, parse_macro_input};
 ///     message: String
 /// }
 /// ```
+///
+/// Besides the ```Serialize``` and ```Deserialize``` implementations every datatype gets helpers that work
+/// on byte slices. ```serialize_into``` writes into a caller provided buffer without allocating and returns
+/// the number of bytes written, a buffer that is too small fails with ```ErrorKind::WriteZero```.
+/// ```to_bytes``` allocates a buffer with the ```size()``` of the datatype and panics if the datatype cannot
+/// be serialized, ```try_to_bytes``` returns the error instead. ```from_bytes``` returns the datatype with the
+/// number of bytes consumed. ```write_to``` serializes into any writer implementing
+/// ```Write``` and ```Seek```, ```serialize_into``` and ```to_bytes``` use it with a ```Cursor``` over the
+/// buffer. This costs a bounds check per write compared to writing into the slice by hand, but keeps a
+/// single implementation for offsets, alignment and checksums that need the position within the output:
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// # #[datatype] pub struct TimedMessage { seconds: u8, message: String }
+/// # fn example(message: TimedMessage) -> std::io::Result<()> {
+/// let mut buffer = [0u8; 64];
+/// let written = message.serialize_into(&mut buffer)?;
+///
+/// let (message, consumed) = TimedMessage::from_bytes(&buffer[..written])?;
+/// let bytes: Vec<u8> = message.to_bytes();
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Nested datatypes and collection elements are written through the ```WriteTo``` trait of the runtime,
+/// which every datatype implements with its ```write_to```. Types with a hand-written ```Serialize```
+/// implementation have to implement it as well to be nested, otherwise the datatype does not compile:
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// pub trait WriteTo {
+///     fn write_to<W: std::io::Write + std::io::Seek>(&self, writer: &mut W) -> std::io::Result<()>;
+/// }
+/// ```
 /// 
 ///
 /// ## Skipping (De-)Serialization
 /// You can skip the generation of (de-)serialize function by omitting the corresponding keyword.
+/// Without ```serialize``` or ```deserialize``` both are generated, the byte slice and async helpers
+/// only exist for the generated directions. Datatypes with ```pod``` or ```codec``` always need both.
 /// This example skips deserialization
 ///
-/// ```rust
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
 /// #[datatype(serialize)]
 /// pub struct TimedMessage
 /// {
@@ -58,11 +110,15 @@ use syn::{DeriveInput, parse_macro_input};
 /// Usually all attributes are (de-)serialized using big endianess but you can change this
 /// per attribute (only for numbers and datatypes that are not u8 and i8):
 ///
-/// ```rust
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
 /// #[datatype]
 /// pub struct TimedMessage
 /// {
-///     #[field(endiness=LittleEndian)]
+///     #[field(endianness=LittleEndian)]
 ///     seconds: u16,
 ///     minutes: u16,
 ///     hours:   u16,
@@ -72,7 +128,11 @@ use syn::{DeriveInput, parse_macro_input};
 ///
 /// It is also possible to change the endianess for all fields like this:
 ///
-/// ```rust
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
 /// #[datatype(endianness=LittleEndian)]
 /// pub struct TimedMessage
 /// {
@@ -85,19 +145,481 @@ use syn::{DeriveInput, parse_macro_input};
 /// ## Collections
 /// Collections are usually (de-)serialized by an additional field of type u16 for the
 /// collection length. This can be adapted per attribute:
-/// ```rust
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
 /// #[datatype]
 /// pub struct TimedMessage
 /// {
-///     #[field(endiness=LittleEndian)]
+///     #[field(endianness=LittleEndian)]
 ///     seconds: u8,
 ///     minutes: u8,
 ///     hours:   u8,
 ///     
-///     #[field(length_type=u8)]
+///     #[field(length_ty=u8)]
 ///     messages: Vec<String>
 /// }
 /// ```
+///
+/// If the number of elements is already part of your datatype use ```count``` to reference the field
+/// instead. No extra length field is generated, the referenced field is always serialized from the
+/// length of the collection and drives the deserialization:
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// # #[datatype] pub struct Entry { id: u8 }
+/// #[datatype]
+/// pub struct Log
+/// {
+///     num_entries: u16,
+///     flags: u8,
+///
+///     #[field(count=num_entries)]
+///     entries: Vec<Entry>
+/// }
+/// ```
+/// ```count``` also accepts any expression of already deserialized fields like ```count=num_pairs as usize * 2```.
+/// In this case you are responsible for keeping the referenced fields in sync with the collection.
+///
+/// Arrays like ```[T; N]``` always hold ```N``` elements, a given length has to match. Their elements
+/// are read in place without allocating and therefore have to implement ```Default```.
+///
+/// Container formats like RIFF often store the size of the payload in bytes instead of the number
+/// of elements. Use ```byte_len_ty``` for a separate length field or ```byte_len``` to reference an
+/// existing field (or expression), both work like their element counting counterparts:
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// # #[datatype] pub struct Chunk { id: [u8; 4] }
+/// # #[datatype] pub struct Record { id: u8 }
+/// #[datatype]
+/// pub struct Container
+/// {
+///     #[field(byte_len_ty=u32, endianness=LittleEndian)]
+///     chunks: Vec<Chunk>,
+///
+///     payload_size: u16,
+///
+///     #[field(byte_len=payload_size)]
+///     payload: Vec<Record>
+/// }
+/// ```
+/// The byte length is calculated with ```size()``` on serialization. On deserialization elements are
+/// read until exactly the given amount of bytes is consumed, elements that exceed it result in an error.
+///
+/// Collections without any length are supported as well. ```until``` reads elements until one equals
+/// the given terminator, ```until_eof``` reads elements until the input is exhausted and must therefore
+/// be the last field:
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// #[datatype]
+/// pub struct Path
+/// {
+///     #[field(until=0)]
+///     segments: Vec<u16>,
+///
+///     #[field(until_eof)]
+///     trailer: Vec<u8>
+/// }
+/// ```
+/// The terminator is written after the elements and is not part of the collection. Serializing a
+/// collection that contains its terminator results in an error.
+///
+/// ### Bounded collections
+/// Containers with a fixed capacity like ```heapless::Vec<T, N>```, ```arrayvec::ArrayVec<T, N>``` (or
+/// ```ArrayVec<[T; N]>```) and ```heapless::String<N>``` are (de-)serialized like ```Vec``` and ```String```
+/// with the same attributes and wire format, but are filled in place without any allocation:
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// #[datatype]
+/// pub struct Frame
+/// {
+///     #[field(length_ty=u8)]
+///     samples: heapless::Vec<u16, 32>,
+///
+///     #[field(string=prefixed(u8))]
+///     label: heapless::String<16>
+/// }
+/// ```
+/// The capacity is an upper bound of the length, longer inputs are rejected on deserialization.
+/// Strings that need to be decoded like ```latin1``` are converted to UTF-8 before they are copied.
+///
+/// ## Variable-length integers
+/// Integers can be stored with a variable amount of bytes instead of their fixed width by using the
+/// ```varint``` keyword. Unsigned integers are encoded as LEB128, signed ones as signed LEB128. Use
+/// ```zigzag``` for Protobuf-style zigzag encoding of signed integers and ```varint=vlq``` for
+/// VLQ (most significant group first, as used by MIDI). Collection lengths can use varints as well
+/// with ```length_ty=varint``` (or ```length_ty=vlq```):
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// # #[datatype] pub struct Sample { value: u16 }
+/// #[datatype]
+/// pub struct Deltas
+/// {
+///     #[field(varint)]
+///     timestamp: u64,
+///
+///     #[field(zigzag)]
+///     delta: i32,
+///
+///     #[field(length_ty=varint)]
+///     samples: Vec<Sample>
+/// }
+/// ```
+/// The generated ```size()``` function returns the actual encoded width. Varints that are longer than
+/// the maximal width of the type or whose value does not fit into the type are rejected on deserialization.
+///
+/// ## Decimal integers
+/// Legacy devices often store numbers as decimal digits. ```bcd``` packs two digits into each byte and uses
+/// as many bytes as the integer type per default, ```ascii_decimal``` writes ASCII digits padded with leading
+/// zeros and uses as many digits as the maximum of the type has. Both accept a different width:
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// #[datatype]
+/// pub struct TimedMessage
+/// {
+///     #[field(bcd)]
+///     hours: u8,
+///     #[field(bcd)]
+///     minutes: u8,
+///     #[field(bcd)]
+///     seconds: u8,
+///
+///     #[field(ascii_decimal(width=6))]
+///     id: u32,
+///
+///     #[field(bcd(width=3))]
+///     date: u32
+/// }
+/// ```
+/// 23:59:07 is stored as ```0x23 0x59 0x07``` and the id 123 as ```"000123"```. Invalid digits and values that do
+/// not fit into the integer are rejected on deserialization, negative values and values with too many digits on
+/// serialization. BCD bytes are reversed with ```endianness=LittleEndian```.
+///
+/// ## Scaled and fixed point numbers
+/// Sensor values are often transmitted as integers in a fixed resolution. Float fields with ```raw```
+/// are (de-)serialized as an integer of the given type, ```scale``` and ```offset``` convert it with
+/// ```value = raw * scale + offset```. Fixed point formats can be given directly with ```fixed```:
+/// ```q15``` has a sign bit and 15 fractional bits, ```q16_16``` has 16 integer bits (including the sign)
+/// and 16 fractional bits. Unsigned formats like ```uq8_8``` are prefixed with ```uq```.
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// #[datatype]
+/// pub struct Measurement
+/// {
+///     #[field(raw=i16, scale=0.01, offset=-40.0)]
+///     temperature: f32,
+///
+///     #[field(fixed=q16_16)]
+///     position: f64
+/// }
+/// ```
+/// On serialization values are rounded to the nearest raw value, values that do not fit into the raw type
+/// result in an error.
+///
+/// Floats can also use a different format on the wire with ```float```: ```f16``` (IEEE half precision),
+/// ```bf16``` (bfloat16), ```ibm32``` and ```ibm64``` (IBM hexadecimal floats) as well as ```vax_f``` and
+/// ```vax_g``` (VAX F and G floats). The field itself stays a ```f32``` or ```f64```:
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// #[datatype]
+/// pub struct Record
+/// {
+///     #[field(float=f16, endianness=LittleEndian)]
+///     weight: f32,
+///
+///     #[field(float=ibm64)]
+///     depth: f64
+/// }
+/// ```
+/// Values are rounded to the nearest representable value. Half precision floats overflow to infinity like any
+/// IEEE conversion. IBM floats cannot store NaN and infinities, VAX floats cannot store infinities, these values
+/// as well as too large ones fail the serialization. Too small values become zero. NaN is written as VAX
+/// reserved operand and read back as NaN. VAX floats always use their 16 bit little endian words, most
+/// significant word first, and ignore the endianness.
+///
+/// ## Strings
+/// Per default strings are (de-)serialized C-style and terminated by a null byte. Use the ```string``` keyword
+/// to select a different layout:
+/// * ```string=terminated(0x0A)``` terminates the string with the given byte instead
+/// * ```string=prefixed(u16)``` writes the length in bytes in front of the string (Pascal strings). Any
+///   integer type as well as ```varint``` is possible
+/// * ```string=fixed(32)``` always uses 32 bytes. Shorter strings are padded with null bytes or the byte
+///   given by ```pad```, which is stripped again on deserialization
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// #[datatype]
+/// pub struct Device
+/// {
+///     #[field(string=prefixed(u8))]
+///     vendor: String,
+///
+///     #[field(string=fixed(32), pad=b' ')]
+///     name: String,
+///
+///     #[field(string=terminated(0x0A))]
+///     description: String
+/// }
+/// ```
+/// Serializing a string that does not fit into its fixed width fails. Add ```truncate``` to cut it off instead.
+/// On collections of ```String``` like ```Vec<String>``` the layout and encoding apply to every element.
+///
+/// ### Text encodings
+/// Strings and chars are UTF-8 encoded per default. Other encodings can be selected with ```encoding```,
+/// supported are ```utf8```, ```ascii```, ```latin1```, ```windows1252```, ```utf16le``` and ```utf16be```.
+/// Characters that cannot be represented fail the (de-)serialization unless ```lossy``` is set, in which
+/// case they are replaced by ```?``` (serialization) or ```U+FFFD``` (deserialization). Length prefixes count
+/// bytes unless ```length_unit=code_units``` is used. Terminators and padding of UTF-16 strings are written
+/// as full code units.
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// #[datatype]
+/// pub struct Device
+/// {
+///     #[field(encoding=latin1, lossy)]
+///     vendor: String,
+///
+///     #[field(encoding=utf16le, string=prefixed(u16), length_unit=code_units)]
+///     name: String,
+///
+///     #[field(encoding=ascii)]
+///     unit: char
+/// }
+/// ```
+///
+/// ### Borrowed strings and bytes
+/// Datatypes with a lifetime parameter can borrow from the input instead of allocating. ```&'a str``` and
+/// ```&'a [u8]``` fields point into the deserialized bytes and support the same layouts as ```String``` and
+/// ```Vec<u8>```, borrowed strings must be UTF-8 encoded. ```Cow<'a, str>``` borrows valid UTF-8 strings
+/// and holds an owned ```String``` for other encodings or replaced characters. Such datatypes do not implement
+/// ```Deserialize``` but provide ```read_from``` and a ```from_bytes``` that borrows from its argument:
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// # use std::borrow::Cow;
+/// #[datatype]
+/// pub struct Frame<'a>
+/// {
+///     #[field(string=prefixed(u8))]
+///     name: &'a str,
+///
+///     #[field(encoding=latin1)]
+///     vendor: Cow<'a, str>,
+///
+///     #[field(until_eof)]
+///     payload: &'a [u8]
+/// }
+///
+/// # fn example(capture: Vec<u8>) -> std::io::Result<()> {
+/// let (frame, _) = Frame::from_bytes(&capture)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ## Magic numbers
+/// Many file formats and protocols start with a constant to identify them. Use ```magic``` on the datatype
+/// to write a byte string in front of all fields or on a field to write a constant at its position. Integer
+/// magics are written with the type and endianness of the field.
+///
+/// The constant is checked on deserialization, a mismatch results in an error reporting the expected and
+/// the actual bytes. Like reserved fields, fields with a magic are omitted in the generated datatype.
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// #[datatype(magic=b"RIFF")]
+/// pub struct Riff
+/// {
+///     size: u32,
+///
+///     #[field(magic=0xCAFEBABE, endianness=LittleEndian)]
+///     _signature: u32,
+///
+///     #[field(magic=b"WAVE")]
+///     _format: [u8; 4],
+/// }
+/// ```
+///
+/// ## Checksums
+/// Integer fields can hold a checksum of other fields with ```checksum```. The checksum is calculated
+/// from the bytes that are actually written on serialization and verified on deserialization. ```over```
+/// selects the covered fields in the (de-)serialization order and works like a Rust range, an open start
+/// begins with the first field (including a magic of the datatype) and an open end stops before the
+/// checksum. Without ```over``` all fields before the checksum are covered.
+///
+/// Supported algorithms are ```sum8```, ```xor8```, ```crc16_ccitt``` (CRC-16/CCITT-FALSE), ```crc16_xmodem```
+/// and ```crc32```. Any other type is used as vendor algorithm and must implement the ```Checksum``` trait
+/// next to ```Serialize``` and ```Deserialize```:
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// # pub struct VendorSum;
+/// # impl datatypes::Checksum for VendorSum {
+/// #     fn checksum(data: &[u8]) -> u64 { data.len() as u64 }
+/// # }
+/// pub trait Checksum {
+///     fn checksum(data: &[u8]) -> u64;
+/// }
+///
+/// #[datatype]
+/// pub struct Frame
+/// {
+///     address: u8,
+///     command: u8,
+///
+///     #[field(count=2)]
+///     payload: Vec<u8>,
+///
+///     #[field(checksum=crc16_ccitt, over=(command..=payload))]
+///     crc: u16,
+///
+///     #[field(checksum=VendorSum)]
+///     vendor: u32,
+/// }
+/// ```
+/// The checksum must come after the fields it covers and, like reserved fields, is omitted in the generated
+/// datatype.
+///
+/// ## Alignment and padding
+/// Datatypes generated from C structs often contain padding. ```align``` inserts zero bytes in front of a
+/// field until its offset is a multiple of the given value, ```padding``` inserts a fixed amount of zero bytes.
+/// On the datatype ```align``` pads the end so that the size is a multiple of the value:
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// #[datatype(align=8)]
+/// pub struct Header
+/// {
+///     version: u8,
+///
+///     #[field(align=4)]
+///     flags: u32,
+///
+///     #[field(padding=2)]
+///     kind: u8,
+/// }
+/// ```
+/// Offsets are relative to the start of the datatype. Use ```align_base=stream``` on the datatype to align
+/// relative to the position within the stream instead, ```size()``` then assumes that the datatype starts at
+/// an aligned position. The padding is included in ```size()``` and skipped without checking its content on
+/// deserialization. For collections the padding is inserted in front of the length.
+///
+/// ## Offset-addressed fields
+/// Formats like ELF or TIFF store offsets to data located elsewhere. ```at``` references an integer field
+/// holding the offset of a field, ```offset_ty``` generates the offset in place of the field instead.
+/// Offsets are relative to the start of the datatype unless ```relative_to=stream``` is used:
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// #[datatype]
+/// pub struct Resource
+/// {
+///     kind: u8,
+///     name_offset: u32,
+///
+///     #[field(at=name_offset)]
+///     name: String,
+///
+///     #[field(offset_ty=u32, relative_to=stream)]
+///     data: Vec<u8>,
+/// }
+/// ```
+/// On serialization addressed fields are placed after all other fields in the order of their declaration
+/// and the offsets are filled in. On deserialization the stream seeks to each offset and is afterwards
+/// placed behind the furthest field that has been read. Addressed fields cannot be covered by checksums.
+///
+/// ## Calculated fields
+/// Derived values like the total length of a header are easily forgotten. ```calc``` calculates the value
+/// of an integer or float field on serialization, whatever the struct holds is ignored. Add ```omit``` to
+/// remove the field from the generated datatype and ```verify``` to check on deserialization that the
+/// value matches the recalculated one:
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// #[datatype]
+/// pub struct Packet
+/// {
+///     #[field(calc=self.payload.len() as u16 + 4, verify)]
+///     total_length: u16,
+///
+///     #[field(calc=self.payload.len() as u8, omit)]
+///     count: u8,
+///
+///     #[field(count=count)]
+///     payload: Vec<u8>,
+/// }
+/// ```
+/// Omitted fields can still be referenced by other fields like ```count``` on deserialization. The
+/// calculation is evaluated on the deserialized datatype and can therefore only access fields that
+/// are part of it.
+///
+/// ## Validation
+/// Fields can be constrained with ```range``` and ```one_of``` (integer, float and char fields) as well as
+/// ```assert``` which accepts any boolean expression. The constraints are checked right after the field is
+/// deserialized and fail with an error naming the field. Assertions can therefore only refer to the field
+/// itself and fields that are deserialized before it:
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// #[datatype]
+/// pub struct Time
+/// {
+///     #[field(range=0..24)]
+///     hours: u8,
+///
+///     #[field(range=0..=59, assert=self.minutes % 15 == 0 || self.hours > 0)]
+///     minutes: u8,
+///
+///     #[field(one_of=[1, 2, 4])]
+///     factor: u16
+/// }
+/// ```
+/// Datatypes with constraints additionally get a ```validate()``` method which performs the same checks
+/// and can be used before serializing. Reserved and omitted fields are only checked on deserialization.
+///
 /// ## Reserved Fields
 /// Sometimes your datatype might need reserved fields where the actual content does not matter
 /// but is important for (de-)serialization in order to fulfill already existing interface
@@ -106,7 +628,11 @@ use syn::{DeriveInput, parse_macro_input};
 /// Keep in mind that reserved datafields are omitted in the generated datatype. Therefore you
 /// cannot access the _reserved0 and _reserved1 fields on the struct as they do not exist for your
 /// code.
-/// ```rust
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
 /// #[datatype]
 /// pub struct TimedMessage
 /// {
@@ -123,7 +649,11 @@ use syn::{DeriveInput, parse_macro_input};
 /// ```
 ///
 /// You can also group multiple reserved bytes into one attribute to make your code easier to read.
-/// ```rust
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
 /// #[datatype]
 /// pub struct TimedMessage
 /// {
@@ -143,7 +673,8 @@ use syn::{DeriveInput, parse_macro_input};
 /// 
 /// CURRENTLY NOT IMPLEMENTED
 /// 
-/// ```rust
+/// ```rust,ignore
+/// # use sdk_macro::datatype;
 /// #[datatype]
 /// pub struct TimedMessage
 /// {
@@ -157,14 +688,47 @@ use syn::{DeriveInput, parse_macro_input};
 ///     messages: Vec<String>
 /// }
 /// ```
+/// ## Views
+/// Reading one or two fields of a record does not need to decode all of them. ```view``` generates
+/// ```NameView``` over the serialized bytes with an accessor per field that decodes only this field.
+/// Fields at a static offset are read directly, fields behind variable parts like strings or collections
+/// are found by reading the fields in front of them. ```NameViewMut``` patches integer and float fields in
+/// place with ```set_<field>```, checksums covering the field are updated and ```range``` and ```one_of```
+/// are checked:
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// #[datatype(view)]
+/// pub struct TimedMessage
+/// {
+///     seconds: u8,
+///     minutes: u8,
+///     hours:   u8,
+///     message: String
+/// }
+///
+/// # fn example(mut bytes: Vec<u8>) -> std::io::Result<()> {
+/// let minutes = TimedMessageView::new(&bytes).minutes()?;
+///
+/// let mut view = TimedMessageViewMut::new(&mut bytes);
+/// view.set_hours(12)?;
+/// # Ok(())
+/// # }
+/// ```
 /// ## Aggregated datatypes
 /// It is possible and encouraged to use reuse datatypes in another ones.
 /// The only thing you need to ensure, that each struct uses the ```Datatype``` macro:
-/// ```rust
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
 /// #[datatype]
 /// pub struct Time
 /// {
-///     #[field(endiness=LittleEndian)]
+///     #[field(endianness=LittleEndian)]
 ///     seconds: u8,
 ///     minutes: u8,
 ///     hours:   u8,
@@ -181,7 +745,11 @@ use syn::{DeriveInput, parse_macro_input};
 /// Attributes are (de-)serialized per default in the order how they are defined in a struct with the
 /// topmost being the first and the bottommost the last one that are (de-)serialized. You can
 /// specify an alternate position to comply with legacy definitions by using the ```position``` tag:
-/// ```rust
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
 /// #[datatype]
 /// pub struct Time
 /// {
@@ -197,7 +765,11 @@ use syn::{DeriveInput, parse_macro_input};
 /// * hours
 ///
 /// If you need (de-)serialize the length of a container at a different position you can use the ```length_position``` tag:
-/// ```rust
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
 /// #[datatype]
 /// pub struct TimedMessage
 /// {
@@ -219,7 +791,8 @@ use syn::{DeriveInput, parse_macro_input};
 /// 
 /// CURRENTLY NOT IMPLEMENTED
 /// 
-/// ```rust
+/// ```rust,ignore
+/// # use sdk_macro::datatype;
 /// #[datatype]
 /// pub struct TimedMessage
 /// {
@@ -239,7 +812,7 @@ use syn::{DeriveInput, parse_macro_input};
 /// 
 /// CURRENTLY NOT IMPLEMENTED
 /// 
-/// ```rust
+/// ```rust,ignore
 /// pub struct Time
 /// {
 ///     seconds: u8,
@@ -259,7 +832,7 @@ use syn::{DeriveInput, parse_macro_input};
 /// 
 /// CURRENTLY NOT IMPLEMENTED
 /// 
-/// ```rust
+/// ```rust,ignore
 /// pub enum TimeFormat {
 ///     HHMMSS,
 ///     MMSS,
@@ -285,7 +858,7 @@ use syn::{DeriveInput, parse_macro_input};
 /// 
 /// CURRENTLY NOT IMPLEMENTED
 /// 
-/// ```rust
+/// ```rust,ignore
 /// pub struct Time
 /// {
 ///     seconds: u8,
@@ -303,10 +876,55 @@ use syn::{DeriveInput, parse_macro_input};
 /// ```
 ///
 /// ## Fixed Size
+/// Datatypes whose layout does not depend on any field value get a ```SIZE``` constant with their size in bytes
+/// and implement the ```FixedSize``` trait. Every field must be an integer, float, char, fixed length string,
+/// array or enum and the datatype may not use ```align_base=stream```. ```size()``` then returns the constant
+/// and buffers can be allocated on the stack:
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// pub trait FixedSize {
+///     const SIZE: usize;
+/// }
+///
+/// #[datatype]
+/// pub struct Time
+/// {
+///     seconds: u8,
+///     minutes: u8,
+///     hours:   u8,
+/// }
+///
+/// # fn example() {
+/// let mut buffer = [0u8; Time::SIZE];
+/// # }
+/// ```
+/// Whether a nested datatype is statically sized is not known while expanding the macro. Add ```fixed_size```
+/// to the struct to treat nested datatypes as ```FixedSize``` and to get an error for every field that is
+/// not statically sized. ```fixed_size = N``` additionally checks at compile time that the datatype is
+/// exactly ```N``` bits long, debug builds also check that the written bytes match ```SIZE```:
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// # #[datatype(fixed_size = 24)] pub struct Time { seconds: u8, minutes: u8, hours: u8 }
+/// #[datatype(fixed_size = 56)]
+/// pub struct TimedValue
+/// {
+///     time:  Time,
+///     value: u32,
+/// }
+/// ```
+///
 /// If your datatype always needs to have a fixed size you can enforce it by using the ```size``` attribute on the struct and one ```variable``` attribute
 /// on a data field. The ```size``` attribute specifies the size of the datatype in bits. Fields with the ```variable``` must be of type ```Vec<char>```.
 /// 
-/// ```rust
+/// CURRENTLY NOT IMPLEMENTED
+///
+/// ```rust,ignore
 /// #[datatype(fixed_size=128)]
 /// pub struct VariableTime
 /// {
@@ -322,13 +940,157 @@ use syn::{DeriveInput, parse_macro_input};
 ///     iso_string: String,
 /// }
 /// ```
-
+/// ## Plain old data
+/// Datatypes consisting only of integers, floats and arrays of them can be copied as a whole instead of
+/// field by field. ```pod``` lays the struct out with ```#[repr(C, packed)]``` and reads and writes it with
+/// a single copy, fields whose endianness differs from the host are swapped in place afterwards. Every field
+/// is checked at compile time, arrays need ```count = N``` instead of a length prefix and the fields are
+/// (de-)serialized in the order of the struct. Vectors of such datatypes are read with a single copy as well,
+/// ```read_many``` is also available directly:
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// #[datatype(pod)]
+/// #[derive(Clone, Copy)]
+/// pub struct Sample
+/// {
+///     id:    u16,
+///     value: f32,
+///     #[field(count = 3)]
+///     axes:  [i16; 3],
+/// }
+///
+/// # fn example(mut byte_stream: std::io::Cursor<&[u8]>) -> std::io::Result<()> {
+/// let samples = Sample::read_many(&mut byte_stream, 128)?;
+/// # Ok(())
+/// # }
+/// ```
+/// ## Async I/O
+/// With the ```async``` feature every datatype additionally gets ```serialize_async``` and
+/// ```deserialize_async``` working on tokio's ```AsyncWrite``` and ```AsyncRead```, the crate using the
+/// macro needs to depend on tokio with ```io-util```. The datatype is (de-)serialized through a buffer with
+/// the blocking implementation, so nested datatypes and collections behave exactly the same. Only the bytes
+/// of the datatype are consumed from the reader, datatypes with ```until_eof``` read until the end of the
+/// stream. Datatypes without a fixed size need an ```AsyncBufRead``` like ```tokio::io::BufReader```, what
+/// the reader holds is decoded at once and only the bytes of the datatype are consumed. A large length in
+/// the input therefore only allocates as far as the bytes actually arrive. Datatypes borrowing from the
+/// input only get ```serialize_async```:
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// # #[datatype] pub struct TimedMessage { seconds: u8, message: String }
+/// # #[cfg(feature = "async")]
+/// # async fn example(stream: tokio::io::DuplexStream, message: TimedMessage) -> std::io::Result<()> {
+/// let mut stream = tokio::io::BufReader::new(stream);
+/// message.serialize_async(&mut stream).await?;
+/// let message = TimedMessage::deserialize_async(&mut stream).await?;
+/// # Ok(())
+/// # }
+/// ```
+/// ## Incremental decoding
+/// Data received from sockets is often split across reads. ```decode``` tells incomplete input apart from
+/// corrupt input and never consumes anything until the datatype is complete. Incomplete input reports the
+/// least amount of missing bytes if it is known: statically sized datatypes always know it, other datatypes
+/// as long as the next field is statically sized or a collection of primitives with an already decoded
+/// length. Fields like terminated strings or nested datatypes report ```needed: None```. Datatypes with
+/// ```until_eof``` are complete with any input. ```decode``` needs ```Decoded``` to be defined next to the
+/// other traits:
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// # #[datatype] pub struct TimedMessage { seconds: u8, message: String }
+/// pub enum Decoded<T> {
+///     // the datatype and the number of bytes it occupies
+///     Complete(T, usize),
+///     Incomplete { needed: Option<usize> },
+/// }
+///
+/// # fn example(mut buffer: Vec<u8>, received: Vec<u8>) -> std::io::Result<()> {
+/// # use datatypes::Decoded;
+/// buffer.extend_from_slice(&received);
+/// while let Decoded::Complete(message, consumed) = TimedMessage::decode(&buffer)? {
+///     buffer.drain(..consumed);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+/// ## Codecs
+/// ```codec``` generates ```NameCodec``` implementing ```Decoder``` and ```Encoder``` of tokio-util to put the
+/// datatype on a ```Framed``` stream, the crate using the macro needs to depend on tokio-util and bytes.
+/// Frames are delimited by the datatype itself using ```decode```, ```codec(length_ty = u32)``` prefixes
+/// every frame with its length in big endian instead which is required for datatypes with ```until_eof```.
+/// Frames larger than ```max_frame_size``` (8 MiB by default) are rejected on encoding and on decoding
+/// before they are buffered:
+/// ```rust,no_run
+/// # mod datatypes { include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/common/datatypes.rs")); }
+/// # use datatypes::{Deserialize, Serialize};
+/// # use sdk_macro::datatype;
+/// # fn main() {}
+/// #[datatype(codec(length_ty = u16, max_frame_size = 4096))]
+/// pub struct TimedMessage
+/// {
+///     seconds: u8,
+///     minutes: u8,
+///     hours:   u8,
+///     message: String
+/// }
+///
+/// # async fn example(stream: tokio::io::DuplexStream, message: TimedMessage) -> std::io::Result<()> {
+/// # use futures::{SinkExt, StreamExt};
+/// # use tokio_util::codec::Framed;
+/// let mut framed = Framed::new(stream, TimedMessageCodec::new());
+/// framed.send(message).await?;
+/// let message = framed.next().await.transpose()?;
+/// # Ok(())
+/// # }
+/// ```
+/// ## no_std
+/// With the ```no_std``` feature the generated code only depends on ```core```, ```alloc``` and an ```io```
+/// module of the runtime next to the traits, so one datatype serves firmware and host. Instead of
+/// ```std::io``` it uses ```crate::datatypes::io``` which provides ```Cursor```, ```Error```, ```ErrorKind```,
+/// ```Result```, the ```Read```, ```BufRead```, ```Write``` and ```Seek``` traits with the methods of their
+/// std counterparts used by the generated code, ```ReadBytesExt``` and ```WriteBytesExt``` with the
+/// methods of byteorder and the ```Writer``` passed to ```Serialize```. Byte orders are still taken from
+/// byteorder without default features. ```Vec``` and ```String``` come from ```alloc```,
+/// arrays, padding and all other fields do not allocate and errors carry static messages. Only the
+/// serialization of checksums buffers the covered fields in a ```Vec```. Field types
+/// and expressions of ```calc``` or ```assert``` are used as written, so they have to name types that exist without std. Codecs and the ```async``` feature require std:
+/// ```rust,ignore
+/// #![no_std]
+/// extern crate alloc;
+///
+/// pub mod datatypes {
+///     pub mod io {
+///         pub type Writer = Cursor<alloc::vec::Vec<u8>>;
+///         // Cursor, Error, ErrorKind, Result, Read, BufRead, Write, Seek, ReadBytesExt, WriteBytesExt
+///     }
+///
+///     pub trait Serialize {
+///         fn serialize(&self, writer: &mut io::Writer) -> io::Result<()>;
+///         fn size(&self) -> u32;
+///     }
+///
+///     pub trait WriteTo {
+///         fn write_to<W: io::Write + io::Seek>(&self, writer: &mut W) -> io::Result<()>;
+///     }
+/// }
+/// ```
 #[proc_macro_error]
 #[proc_macro_attribute]
-pub fn datatype(_: TokenStream, input: TokenStream) -> TokenStream {
+pub fn datatype(args: TokenStream, input: TokenStream) -> TokenStream {
     
+    let args = parse_macro_input!(args as DataStructArgs);
+    let mut datatype_struct = parse_macro_input!(input as ItemStruct);
+
+    // fields without their own endianness use the one of the datatype
+    apply_struct_endianness(&mut datatype_struct, &args);
 
-    let datatype_struct = parse_macro_input!(input as ItemStruct);
     let mut attrs: Vec<DatatypeAttribute> = datatype_struct.fields
                                             .iter()
                                             .map(|x| Into::<Vec<DatatypeAttribute>>::into(x))
@@ -341,8 +1103,15 @@ pub fn datatype(_: TokenStream, input: TokenStream) -> TokenStream {
     // reorders the attributes in increasing order
     update(&mut attrs); 
 
+    // adds attributes defined on the datatype itself like a magic
+    apply_struct_args(&mut attrs, &datatype_struct, &args);
+
+    // checks that referenced fields exist and are (de-)serialized before they are used and that
+    // fields reading until the end of the input come last
+    validate_order(&attrs);
+
     // Build the impl
-    data_struct::generate::produce(&datatype_struct, &attrs).into()
+    data_struct::generate::produce(&datatype_struct, &attrs, &args).into()
 }
 
 /// Convenience macro that is capable of generating (de-)serialize functions
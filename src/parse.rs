@@ -2,6 +2,8 @@ use proc_macro_error::abort;
 use quote::ToTokens;
 use syn::Type;
 
+use crate::structs::Storage;
+
 
 
 
@@ -11,9 +13,9 @@ pub(crate) fn is_collection_type(ty: &Type) -> Option<(bool, Option<u8>)> {
         Type::Path(p) => {
             let segments = &p.path.segments;
             
-            if let Some(first) = segments.first() {
+            if let Some(last) = segments.last() {
 
-                let t = first.ident.to_string();
+                let t = last.ident.to_string();
                 let t = t.as_str();
 
                 match t {
@@ -24,7 +26,8 @@ pub(crate) fn is_collection_type(ty: &Type) -> Option<(bool, Option<u8>)> {
                     "BTreeMap" | 
                     "HashSet" | 
                     "BTreeSet" |
-                    "BinaryHeap" => return Some((true, None)),
+                    "BinaryHeap" |
+                    "ArrayVec" => return Some((true, None)),
                     _ => return None
                 }
             }
@@ -36,6 +39,8 @@ pub(crate) fn is_collection_type(ty: &Type) -> Option<(bool, Option<u8>)> {
             
             Some((true, Some(i)))
         }
+        // borrowed slices like &'a [u8]
+        Type::Reference(reference) if matches!(*reference.elem, Type::Slice(_)) => Some((true, None)),
         _ => None,
     }
 }
@@ -47,12 +52,18 @@ pub fn get_collection_embedded_type(ty: &Type) -> (String, Option<u8>) {
         Type::Path(p) => {
             let segments = &p.path.segments;
            
-            if let Some(first) = segments.first() {
-                match &first.arguments {
+            if let Some(last) = segments.last() {
+                match &last.arguments {
                     syn::PathArguments::None => todo!(),
                     // <> like Vec, HashMap etc
                     syn::PathArguments::AngleBracketed(x) => {
-                        return (x.args.first().unwrap().to_token_stream().to_string(), None);
+                        return match x.args.first() {
+                            // ArrayVec<[T; N]> holds its capacity in the array type
+                            Some(syn::GenericArgument::Type(Type::Array(array))) if last.ident == "ArrayVec" => {
+                                (array.elem.to_token_stream().to_string(), None)
+                            }
+                            arg => (arg.unwrap().to_token_stream().to_string(), None),
+                        };
                     },
 
                     // () like groups (u32, String, ...)
@@ -71,6 +82,51 @@ pub fn get_collection_embedded_type(ty: &Type) -> (String, Option<u8>) {
             
             (ty, Some(i))
         },
+        Type::Reference(reference) => match &*reference.elem {
+            Type::Slice(slice) => (slice.elem.to_token_stream().to_string(), None),
+            _ => todo!(),
+        },
         _ => todo!(),
     }
-}
\ No newline at end of file
+}
+// collections with a fixed capacity: heapless::Vec<T, N>, ArrayVec<[T; N]> and ArrayVec<T, N>
+pub(crate) fn is_bounded_collection(ty: &Type) -> bool {
+    match ty {
+        Type::Path(p) => match p.path.segments.last() {
+            Some(last) if last.ident == "ArrayVec" => true,
+            Some(last) if last.ident == "Vec" => match &last.arguments {
+                syn::PathArguments::AngleBracketed(x) => x.args.len() == 2,
+                _ => false,
+            },
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+// storage of string fields: String, &'a str, Cow<'a, str> or heapless::String<N>
+pub(crate) fn string_storage(ty: &Type) -> Option<Storage> {
+    match ty {
+        Type::Path(p) => {
+            let last = p.path.segments.last()?;
+            match last.ident.to_string().as_str() {
+                "String" if last.arguments.is_none() => Some(Storage::Owned),
+                "String" => Some(Storage::Bounded(ty.to_token_stream().to_string())),
+                "Cow" => match &last.arguments {
+                    syn::PathArguments::AngleBracketed(x) => x
+                        .args
+                        .iter()
+                        .any(|arg| arg.to_token_stream().to_string() == "str")
+                        .then_some(Storage::Cow),
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+        Type::Reference(reference) => match reference.elem.to_token_stream().to_string().as_str() {
+            "str" => Some(Storage::Borrowed),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -1,7 +1,7 @@
 use proc_macro2::Span;
-use syn::Ident;
+use syn::{Expr, Ident};
 
-use crate::types::{int::IntegerType, float::FloatType};
+use crate::types::{checksum::{ChecksumAlgorithm, ChecksumRange}, decimal::DecimalFormat, int::IntegerType, float::{FloatEncoding, FloatType}, scaled::Scaling, string::{StringFormat, TextEncoding}, varint::VarintEncoding};
 
 #[derive(Debug, Clone, PartialEq)]
 
@@ -51,17 +51,118 @@ impl<'a> Into<&'a str> for &DatatypeEndianness {
 
 
 
+/// Unit in which the length of a collection is given
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum LengthUnit {
+    Elements,
+    Bytes,
+}
+
+/// Zero bytes that are written in front of a field or at the end of the datatype
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Padding {
+    // the given amount of bytes
+    Fixed(u32),
+    // as many bytes as needed to reach a multiple of the alignment
+    Align(u32),
+}
+
+/// Value of a field that is calculated on serialization
+#[derive(Debug, Clone)]
+pub(crate) struct Calc {
+    pub(crate) expr: Expr,
+    // the deserialized value must match the recalculated one
+    pub(crate) verify: bool,
+    // the field is not part of the generated struct
+    pub(crate) omit: bool,
+}
+
+/// Check of a field value on deserialization and validation
+#[derive(Debug, Clone)]
+pub(crate) enum Constraint {
+    // the value must be contained in the range
+    Range(Expr),
+    // the value must equal one of the listed values
+    OneOf(Vec<Expr>),
+    // the expression must evaluate to true
+    Assert(Expr),
+}
+
+/// Position an offset is relative to
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum OffsetBase {
+    // start of the datatype
+    Start,
+    // start of the stream
+    Stream,
+}
+
+/// Location of a field that is not (de-)serialized in sequence but placed after all other fields
+/// and referenced by an offset
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Address {
+    // variable holding the offset on deserialization
+    pub(crate) offset: Ident,
+    pub(crate) base: OffsetBase,
+}
+
+/// Determines how many elements of a collection are (de-)serialized
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum CollectionBound {
+    // separate CollectionLength attribute
+    Prefix(LengthUnit),
+    // another integer field of the datatype holds the length
+    Field(Ident, LengthUnit),
+    // the length is calculated from already deserialized fields
+    Expr(Expr, LengthUnit),
+    // elements are read until one equals the terminator which is not part of the collection
+    Terminator(Expr),
+    // elements are read until the end of the input
+    Eof,
+}
+
+/// How a string or byte collection holds its data after deserialization
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Storage {
+    Owned,
+    // borrowed from the input like &'a str or &'a [u8]
+    Borrowed,
+    // Cow<'a, str>, borrowed if the input can be used as is
+    Cow,
+    // container with a fixed capacity like heapless::Vec<T, N> or heapless::String<N>, holds the
+    // type of the container
+    Bounded(String),
+}
+
 #[derive(Debug)]
 
 pub(crate) enum DatatypeAttributeType {
     PrimitiveInteger(IntegerType),
+    // integer stored with a variable amount of bytes
+    VarInt(IntegerType, VarintEncoding),
     PrimitiveFloat(FloatType),
+    // integer stored as decimal digits
+    Decimal(IntegerType, DecimalFormat),
+    // float transmitted as scaled raw integer
+    Scaled(FloatType, Scaling),
+    // float transmitted in a different format like half precision
+    EncodedFloat(FloatType, FloatEncoding),
     Struct(String),
-    String,
-    // length type, position of length attribute (if None than the normal order will be used)
-    Collection(String, Option<u8>),
-    // the length of the collection as seperate attribute so that it can easily positioned arbitrarily
-    CollectionLength(IntegerType),
+    String(StringFormat, TextEncoding, Storage),
+    Char(TextEncoding),
+    // length type, position of length attribute (if None than the normal order will be used), number of elements
+    Collection(String, Option<u8>, CollectionBound, Storage),
+    // the length of the collection as seperate attribute so that it can easily positioned arbitrarily,
+    // optionally stored as varint, either counting elements or bytes
+    CollectionLength(IntegerType, Option<VarintEncoding>, LengthUnit),
+    // constant bytes that are written on serialization and checked on deserialization
+    Magic(Vec<u8>),
+    // integer calculated over the bytes of other fields on serialization and verified on deserialization
+    Checksum(IntegerType, ChecksumAlgorithm, ChecksumRange),
+    // zero bytes that are skipped on deserialization
+    Padding(Padding),
+    // the offset of an addressed field as separate attribute, similar to CollectionLength
+    Offset(IntegerType),
 }
 
 #[derive(Debug)]
@@ -72,4 +173,18 @@ pub(crate) struct DatatypeAttribute {
     pub(crate) position: Option<u8>,
     pub(crate) position_span: Option<Span>,
     pub(crate) reserved: bool,
+    // set if the attribute holds the length of the named collection
+    pub(crate) length_of: Option<(Ident, LengthUnit)>,
+    // padding in front of the attribute, replaced by separate attributes after ordering
+    pub(crate) padding: Vec<Padding>,
+    // set if the attribute is placed after all other fields and referenced by an offset
+    pub(crate) address: Option<Address>,
+    // set if the attribute holds the offset of the named field
+    pub(crate) offset_of: Option<Ident>,
+    // set if the value is calculated on serialization
+    pub(crate) calc: Option<Calc>,
+    // checks of the value, only set on the attribute holding the data of the field
+    pub(crate) constraints: Vec<Constraint>,
+    // format and encoding of the elements of String collections
+    pub(crate) element_string: Option<(StringFormat, TextEncoding)>,
 }
\ No newline at end of file
@@ -0,0 +1,58 @@
+use syn::{Ident, Path};
+
+/// Algorithms to calculate checksum fields
+#[derive(PartialEq, Debug, Clone)]
+pub(crate) enum ChecksumAlgorithm {
+    /// Wrapping sum of all bytes
+    Sum8,
+    /// All bytes combined with xor
+    Xor8,
+    /// CRC-16/CCITT-FALSE (polynomial 0x1021, initial value 0xFFFF)
+    Crc16Ccitt,
+    /// CRC-16/XMODEM (polynomial 0x1021, initial value 0)
+    Crc16Xmodem,
+    /// CRC-32 as used by zip, png and ethernet
+    Crc32,
+    /// User type implementing `crate::datatypes::Checksum`
+    Custom(Path),
+}
+
+impl From<Path> for ChecksumAlgorithm {
+    fn from(path: Path) -> Self {
+        let ident = match path.get_ident() {
+            Some(ident) => ident.to_string(),
+            None => return ChecksumAlgorithm::Custom(path),
+        };
+
+        match ident.as_str() {
+            "sum8" => ChecksumAlgorithm::Sum8,
+            "xor8" => ChecksumAlgorithm::Xor8,
+            "crc16" | "crc16_ccitt" => ChecksumAlgorithm::Crc16Ccitt,
+            "crc16_xmodem" => ChecksumAlgorithm::Crc16Xmodem,
+            "crc32" => ChecksumAlgorithm::Crc32,
+            _ => ChecksumAlgorithm::Custom(path),
+        }
+    }
+}
+
+impl ChecksumAlgorithm {
+    /// Size of the checksum in bytes, None for custom algorithms
+    pub(crate) fn size(&self) -> Option<u32> {
+        match self {
+            ChecksumAlgorithm::Sum8 | ChecksumAlgorithm::Xor8 => Some(1),
+            ChecksumAlgorithm::Crc16Ccitt | ChecksumAlgorithm::Crc16Xmodem => Some(2),
+            ChecksumAlgorithm::Crc32 => Some(4),
+            ChecksumAlgorithm::Custom(_) => None,
+        }
+    }
+}
+
+/// Fields covered by a checksum. Both ends are optional, an open start begins with the first field
+/// and an open end stops right before the checksum field.
+#[derive(PartialEq, Debug, Clone)]
+pub(crate) struct ChecksumRange {
+    pub(crate) start: Option<Ident>,
+    pub(crate) end: Option<Ident>,
+    /// The end field is covered as well (`..=`)
+    pub(crate) inclusive: bool,
+}
@@ -0,0 +1,48 @@
+use crate::types::int::IntegerType;
+
+/// Integer stored as decimal digits instead of binary
+#[derive(PartialEq, Debug, Clone)]
+pub(crate) enum DecimalFormat {
+    // packed BCD, two digits per byte, with the width in bytes
+    Bcd(u32),
+    // ASCII digits padded with leading zeros, with the width in digits
+    Ascii(u32),
+}
+
+impl DecimalFormat {
+    // widths are limited by the digits that fit into an u128
+    pub(crate) const MAX_BCD_WIDTH: u32 = 16;
+    pub(crate) const MAX_ASCII_WIDTH: u32 = 39;
+
+    // BCD uses as many bytes as the integer type per default, ASCII as many digits as its maximum has
+    pub(crate) fn bcd(t: &IntegerType, width: Option<u32>) -> Self {
+        DecimalFormat::Bcd(width.unwrap_or(t.size()))
+    }
+
+    pub(crate) fn ascii(t: &IntegerType, width: Option<u32>) -> Self {
+        let digits = match t {
+            IntegerType::U8 | IntegerType::I8 => 3,
+            IntegerType::U16 | IntegerType::I16 => 5,
+            IntegerType::U32 | IntegerType::I32 => 10,
+            IntegerType::U64 => 20,
+            IntegerType::I64 => 19,
+            IntegerType::U128 => 39,
+            IntegerType::I128 => 39,
+        };
+
+        DecimalFormat::Ascii(width.unwrap_or(digits))
+    }
+
+    pub(crate) fn size(&self) -> u32 {
+        match self {
+            DecimalFormat::Bcd(width) | DecimalFormat::Ascii(width) => *width,
+        }
+    }
+
+    pub(crate) fn digits(&self) -> u32 {
+        match self {
+            DecimalFormat::Bcd(width) => width * 2,
+            DecimalFormat::Ascii(width) => *width,
+        }
+    }
+}
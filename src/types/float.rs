@@ -1,5 +1,5 @@
 use quote::ToTokens;
-use syn::{Type, spanned::Spanned};
+use syn::{Ident, Type, spanned::Spanned};
 
 #[derive(Debug, Clone)]
 pub(crate) enum FloatType {
@@ -66,4 +66,57 @@ impl FloatType {
             FloatType::F64 => 8,
         }
     }
-}
\ No newline at end of file
+}
+/// Wire format of a float field that differs from the IEEE format of its type
+#[derive(PartialEq, Debug, Clone)]
+pub(crate) enum FloatEncoding {
+    // IEEE 754 half precision
+    F16,
+    // upper half of an IEEE 754 single precision float
+    Bf16,
+    // IBM hexadecimal floats in single and double precision
+    Ibm32,
+    Ibm64,
+    // VAX F and G floats
+    VaxF,
+    VaxG,
+}
+
+impl TryFrom<Ident> for FloatEncoding {
+    type Error = syn::Error;
+
+    fn try_from(ident: Ident) -> Result<Self, Self::Error> {
+        match ident.to_string().as_str() {
+            "f16" => Ok(FloatEncoding::F16),
+            "bf16" => Ok(FloatEncoding::Bf16),
+            "ibm32" => Ok(FloatEncoding::Ibm32),
+            "ibm64" => Ok(FloatEncoding::Ibm64),
+            "vax_f" => Ok(FloatEncoding::VaxF),
+            "vax_g" => Ok(FloatEncoding::VaxG),
+            _ => Err(syn::Error::new(ident.span(), "Invalid float encoding, expected 'f16', 'bf16', 'ibm32', 'ibm64', 'vax_f' or 'vax_g'")),
+        }
+    }
+}
+
+impl<'a> From<&FloatEncoding> for &'a str {
+    fn from(encoding: &FloatEncoding) -> &'a str {
+        match encoding {
+            FloatEncoding::F16 => "f16",
+            FloatEncoding::Bf16 => "bf16",
+            FloatEncoding::Ibm32 => "ibm32",
+            FloatEncoding::Ibm64 => "ibm64",
+            FloatEncoding::VaxF => "vax_f",
+            FloatEncoding::VaxG => "vax_g",
+        }
+    }
+}
+
+impl FloatEncoding {
+    pub(crate) fn size(&self) -> u32 {
+        match self {
+            FloatEncoding::F16 | FloatEncoding::Bf16 => 2,
+            FloatEncoding::Ibm32 | FloatEncoding::VaxF => 4,
+            FloatEncoding::Ibm64 | FloatEncoding::VaxG => 8,
+        }
+    }
+}
@@ -38,7 +38,7 @@ impl TryFrom<Ident> for IntegerType {
             "u64"  => Ok(IntegerType::U64),
             "i64"   => Ok(IntegerType::I64),
             "u128"  => Ok(IntegerType::U128),
-            "i128 " => Ok(IntegerType::I128),
+            "i128" => Ok(IntegerType::I128),
             _ => Err(syn::Error::new(ident.span(), "Invalid CollectionLenghType value"))
         }
     }
@@ -110,7 +110,7 @@ impl TryFrom<&str> for IntegerType {
             "u64"  => Ok(IntegerType::U64),
             "i64"   => Ok(IntegerType::I64),
             "u128"  => Ok(IntegerType::U128),
-            "i128 " => Ok(IntegerType::I128),
+            "i128" => Ok(IntegerType::I128),
             _ => Err("Invalid CollectionLenghType value")
         }
     }
@@ -154,4 +154,11 @@ impl IntegerType {
             IntegerType::I128 => 16,
         }
     }
+
+    pub(crate) fn is_signed(&self) -> bool {
+        matches!(
+            self,
+            IntegerType::I8 | IntegerType::I16 | IntegerType::I32 | IntegerType::I64 | IntegerType::I128
+        )
+    }
 }
\ No newline at end of file
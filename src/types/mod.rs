@@ -1,2 +1,7 @@
+pub(crate) mod checksum;
+pub(crate) mod decimal;
 pub(crate) mod float;
-pub(crate) mod int;
\ No newline at end of file
+pub(crate) mod int;
+pub(crate) mod scaled;
+pub(crate) mod string;
+pub(crate) mod varint;
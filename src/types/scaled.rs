@@ -0,0 +1,47 @@
+use syn::Ident;
+
+use crate::types::int::IntegerType;
+
+/// Float field that is transmitted as raw integer, the value is `raw * scale + offset`
+#[derive(PartialEq, Debug, Clone)]
+pub(crate) struct Scaling {
+    pub(crate) raw: IntegerType,
+    pub(crate) scale: f64,
+    pub(crate) offset: f64,
+}
+
+impl TryFrom<Ident> for Scaling {
+    type Error = syn::Error;
+
+    // fixed point formats like q15 (15 fractional bits and a sign bit) or q16_16 (16 integer bits
+    // including the sign and 16 fractional bits), unsigned formats are prefixed with uq
+    fn try_from(ident: Ident) -> Result<Self, Self::Error> {
+        let error = || syn::Error::new(ident.span(), "Invalid fixed point format, expected something like q15, q16_16 or uq8_8");
+
+        let value = ident.to_string();
+        let (signed, format) = match value.strip_prefix("uq") {
+            Some(format) => (false, format),
+            None => (true, value.strip_prefix('q').ok_or_else(error)?),
+        };
+
+        let (integer, fraction) = match format.split_once('_') {
+            Some((integer, fraction)) => (integer.parse::<u32>().map_err(|_| error())?, fraction.parse::<u32>().map_err(|_| error())?),
+            // the sign bit is not counted in the short form
+            None => (signed as u32, format.parse::<u32>().map_err(|_| error())?),
+        };
+
+        let raw = match (integer + fraction, signed) {
+            (8, true) => IntegerType::I8,
+            (16, true) => IntegerType::I16,
+            (32, true) => IntegerType::I32,
+            (64, true) => IntegerType::I64,
+            (8, false) => IntegerType::U8,
+            (16, false) => IntegerType::U16,
+            (32, false) => IntegerType::U32,
+            (64, false) => IntegerType::U64,
+            (bits, _) => return Err(syn::Error::new(ident.span(), format!("Fixed point formats need 8, 16, 32 or 64 bits, {} has {}", value, bits))),
+        };
+
+        Ok(Scaling { raw, scale: 2f64.powi(-(fraction as i32)), offset: 0.0 })
+    }
+}
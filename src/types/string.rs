@@ -0,0 +1,102 @@
+use syn::Ident;
+
+use super::{int::IntegerType, varint::VarintEncoding};
+
+/// Layout of a string on the wire.
+#[derive(PartialEq, Debug, Clone)]
+pub(crate) enum StringFormat {
+    /// Bytes followed by the terminator, C-style strings use 0
+    Terminated(u8),
+    /// Length in bytes followed by the bytes, e.g. Pascal strings
+    Prefixed(IntegerType, Option<VarintEncoding>),
+    /// Always the given amount of bytes, shorter strings are filled with `pad`. Strings that are
+    /// too long are either rejected or truncated on serialization.
+    Fixed { width: usize, pad: u8, truncate: bool },
+}
+
+impl Default for StringFormat {
+    fn default() -> Self {
+        StringFormat::Terminated(0)
+    }
+}
+
+/// Character set used to convert between Rust strings and bytes on the wire.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub(crate) enum Charset {
+    #[default]
+    Utf8,
+    Ascii,
+    Latin1,
+    Windows1252,
+    Utf16Le,
+    Utf16Be,
+}
+
+impl TryFrom<Ident> for Charset {
+    type Error = syn::Error;
+
+    fn try_from(ident: Ident) -> Result<Self, Self::Error> {
+        let value = ident.to_string();
+        let value = value.as_str();
+        match value {
+            "utf8" => Ok(Charset::Utf8),
+            "ascii" => Ok(Charset::Ascii),
+            "latin1" => Ok(Charset::Latin1),
+            "windows1252" | "cp1252" => Ok(Charset::Windows1252),
+            "utf16le" => Ok(Charset::Utf16Le),
+            "utf16be" => Ok(Charset::Utf16Be),
+            _ => Err(syn::Error::new(ident.span(), "Invalid encoding value, expected one of utf8, ascii, latin1, windows1252, utf16le or utf16be"))
+        }
+    }
+}
+
+impl<'a> From<&Charset> for &'a str {
+    fn from(charset: &Charset) -> &'a str {
+        match charset {
+            Charset::Utf8 => "utf8",
+            Charset::Ascii => "ascii",
+            Charset::Latin1 => "latin1",
+            Charset::Windows1252 => "windows1252",
+            Charset::Utf16Le => "utf16le",
+            Charset::Utf16Be => "utf16be",
+        }
+    }
+}
+
+impl Charset {
+    /// Size of one code unit in bytes
+    pub(crate) fn unit_size(&self) -> usize {
+        match self {
+            Charset::Utf16Le | Charset::Utf16Be => 2,
+            _ => 1,
+        }
+    }
+
+    /// Code unit with the value of `byte` as it is written to the wire
+    pub(crate) fn unit(&self, byte: u8) -> Vec<u8> {
+        match self {
+            Charset::Utf16Le => vec![byte, 0],
+            Charset::Utf16Be => vec![0, byte],
+            _ => vec![byte],
+        }
+    }
+}
+
+/// Text encoding of String and char fields.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub(crate) struct TextEncoding {
+    pub(crate) charset: Charset,
+    /// Replace unmappable characters instead of failing
+    pub(crate) lossy: bool,
+    /// Length prefixes count code units instead of bytes
+    pub(crate) count_units: bool,
+}
+
+/// Unicode code points of the bytes 0x80 - 0x9F in Windows-1252. Bytes that are undefined map to
+/// the C1 control character with the same value (as specified by the WHATWG encoding standard).
+pub(crate) const WINDOWS_1252: [u16; 32] = [
+    0x20AC, 0x0081, 0x201A, 0x0192, 0x201E, 0x2026, 0x2020, 0x2021,
+    0x02C6, 0x2030, 0x0160, 0x2039, 0x0152, 0x008D, 0x017D, 0x008F,
+    0x0090, 0x2018, 0x2019, 0x201C, 0x201D, 0x2022, 0x2013, 0x2014,
+    0x02DC, 0x2122, 0x0161, 0x203A, 0x0153, 0x009D, 0x017E, 0x0178,
+];
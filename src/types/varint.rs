@@ -0,0 +1,38 @@
+use syn::Ident;
+
+use super::int::IntegerType;
+
+/// Variable-length wire encodings for integers. All of them store 7 bits per byte and use the
+/// most significant bit of each byte as continuation flag.
+#[derive(PartialEq, Debug, Clone)]
+pub(crate) enum VarintEncoding {
+    /// Least significant group first (unsigned LEB128, signed LEB128 for signed integers)
+    Leb128,
+    /// Protobuf-style zigzag mapping of a signed integer followed by unsigned LEB128
+    Zigzag,
+    /// Most significant group first as used by MIDI and similar formats
+    Vlq,
+}
+
+impl TryFrom<Ident> for VarintEncoding {
+    type Error = syn::Error;
+
+    fn try_from(ident: Ident) -> Result<Self, Self::Error> {
+        let value = ident.to_string();
+        let value = value.as_str();
+        match value {
+            "varint" | "leb128" => Ok(VarintEncoding::Leb128),
+            "zigzag" => Ok(VarintEncoding::Zigzag),
+            "vlq" => Ok(VarintEncoding::Vlq),
+            _ => Err(syn::Error::new(ident.span(), "Invalid VarintEncoding value"))
+        }
+    }
+}
+
+impl VarintEncoding {
+    /// Maximal number of bytes a value of type `ty` can occupy on the wire. Anything longer is
+    /// rejected as malformed on deserialization.
+    pub(crate) fn max_size(&self, ty: &IntegerType) -> u32 {
+        (ty.size() * 8).div_ceil(7)
+    }
+}
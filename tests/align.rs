@@ -0,0 +1,82 @@
+mod common;
+
+pub use common::datatypes;
+use common::{deserialize, serialize, serialize_after};
+use datatypes::{Deserialize, Serialize};
+use sdk_macro::datatype;
+use std::io::Cursor;
+
+#[datatype(align = 8)]
+#[derive(Debug, PartialEq)]
+pub struct Header {
+    version: u8,
+    #[field(align = 4)]
+    flags: u32,
+    #[field(padding = 2)]
+    kind: u8,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Table {
+    kind: u8,
+    #[field(align = 4, length_ty = u8)]
+    entries: Vec<u16>,
+    #[field(align = 2)]
+    end: u16,
+}
+
+#[datatype(align = 4, align_base = stream)]
+#[derive(Debug, PartialEq)]
+pub struct Streamed {
+    kind: u8,
+    #[field(align = 4)]
+    value: u16,
+}
+
+#[test]
+fn layout() {
+    let value = Header { version: 1, flags: 0x02030405, kind: 6 };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, [1, 0, 0, 0, 2, 3, 4, 5, 0, 0, 6, 0, 0, 0, 0, 0]);
+    assert_eq!(value.size(), 16);
+    assert_eq!(deserialize::<Header>(&bytes).unwrap(), value);
+
+    // the padding is skipped without checking it
+    let mut bytes = bytes;
+    bytes[1] = 0xff;
+    bytes[15] = 0xff;
+    assert_eq!(deserialize::<Header>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn collections() {
+    // the padding is inserted in front of the length
+    let value = Table { kind: 1, entries: vec![2, 3], end: 4 };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, [1, 0, 0, 0, 2, 0, 2, 0, 3, 0, 0, 4]);
+    assert_eq!(deserialize::<Table>(&bytes).unwrap(), value);
+
+    let value = Table { kind: 1, entries: vec![2], end: 4 };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, [1, 0, 0, 0, 1, 0, 2, 0, 0, 4]);
+    assert_eq!(deserialize::<Table>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn stream_base() {
+    let value = Streamed { kind: 1, value: 2 };
+
+    // size() assumes an aligned start
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, [1, 0, 0, 0, 0, 2, 0, 0]);
+
+    // starting at an offset of 3 the field is already aligned after the kind
+    let bytes = serialize_after(&[0xff; 3], &value).unwrap();
+    assert_eq!(bytes[3..], [1, 0, 2, 0, 0]);
+
+    let mut byte_stream = Cursor::new(&bytes[..]);
+    byte_stream.set_position(3);
+    assert_eq!(Streamed::deserialize(&mut byte_stream).unwrap(), value);
+    assert_eq!(byte_stream.position(), 8);
+}
@@ -0,0 +1,118 @@
+mod common;
+
+pub use common::datatypes;
+use sdk_macro::{data_enum, datatype};
+use std::io::ErrorKind;
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Time {
+    seconds: u8,
+    minutes: u16,
+}
+
+#[datatype(pod)]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Sample {
+    id: u16,
+    #[field(endianness = LittleEndian)]
+    value: f32,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Samples {
+    count: u16,
+    #[field(count = count)]
+    samples: Vec<Sample>,
+    #[field(string = terminated)]
+    note: String,
+}
+
+#[data_enum(ty = u16)]
+pub enum Kind {
+    A = 1,
+    B = 2,
+}
+
+#[datatype]
+#[derive(Debug)]
+pub struct Large {
+    #[field(length_ty = u32)]
+    data: Vec<u8>,
+}
+
+#[datatype]
+#[derive(Debug)]
+pub struct Huge {
+    #[field(length_ty = varint)]
+    data: Vec<u8>,
+}
+
+#[tokio::test]
+async fn duplex() {
+    // the small buffer forces the reader to wait for the writer several times per datatype
+    let (mut client, server) = tokio::io::duplex(4);
+    let mut server = tokio::io::BufReader::new(server);
+    let time = Time { seconds: 1, minutes: 2 };
+    let samples = Samples { count: 2, samples: vec![Sample { id: 7, value: 1.5 }, Sample { id: 8, value: -2.0 }], note: "hi".into() };
+
+    let writer = async {
+        time.serialize_async(&mut client).await.unwrap();
+        samples.serialize_async(&mut client).await.unwrap();
+        Kind::B.serialize_async(&mut client).await.unwrap();
+        time.serialize_async(&mut client).await.unwrap();
+    };
+    let reader = async {
+        assert_eq!(Time::deserialize_async(&mut server).await.unwrap(), time);
+        assert_eq!(Samples::deserialize_async(&mut server).await.unwrap(), samples);
+        assert_eq!(Kind::deserialize_async(&mut server).await.unwrap(), Kind::B);
+        assert_eq!(Time::deserialize_async(&mut server).await.unwrap(), time);
+    };
+    tokio::join!(writer, reader);
+}
+
+#[tokio::test]
+async fn closed_early() {
+    let (mut client, server) = tokio::io::duplex(16);
+    let mut server = tokio::io::BufReader::new(server);
+    tokio::io::AsyncWriteExt::write_all(&mut client, &[0, 2, 0, 7]).await.unwrap();
+    drop(client);
+    assert_eq!(Samples::deserialize_async(&mut server).await.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
+
+#[tokio::test]
+async fn long_string() {
+    // the end of the string is only known once its terminator arrives, the reader must neither decode
+    // per byte nor consume the following datatype
+    let (mut client, server) = tokio::io::duplex(64);
+    let mut server = tokio::io::BufReader::with_capacity(256, server);
+    let samples = Samples { count: 0, samples: vec![], note: "a".repeat(100_000) };
+    let time = Time { seconds: 1, minutes: 2 };
+
+    let writer = async {
+        samples.serialize_async(&mut client).await.unwrap();
+        time.serialize_async(&mut client).await.unwrap();
+    };
+    let reader = async {
+        assert_eq!(Samples::deserialize_async(&mut server).await.unwrap(), samples);
+        assert_eq!(Time::deserialize_async(&mut server).await.unwrap(), time);
+    };
+    tokio::join!(writer, reader);
+}
+
+#[tokio::test]
+async fn invalid() {
+    let mut input: &[u8] = &[0, 1, 0, 7, 0, 0, 0xc0, 0x3f, 0xff, 0];
+    assert!(Samples::deserialize_async(&mut input).await.is_err());
+}
+
+#[tokio::test]
+async fn large_lengths() {
+    // lengths far beyond the input must not be allocated up front
+    let mut input: &[u8] = &[0xff, 0xff, 0xff, 0xf0, 1, 2];
+    assert_eq!(Large::deserialize_async(&mut input).await.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    let mut input: &[u8] = &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01, 1];
+    assert_eq!(Huge::deserialize_async(&mut input).await.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
@@ -0,0 +1,61 @@
+mod common;
+
+pub use common::datatypes;
+use common::serialize;
+use sdk_macro::datatype;
+use std::borrow::Cow;
+use std::io::ErrorKind;
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Frame<'a> {
+    #[field(string = prefixed(u8))]
+    name: &'a str,
+    text: Cow<'a, str>,
+    #[field(encoding = latin1)]
+    vendor: Cow<'a, str>,
+    #[field(until_eof)]
+    payload: &'a [u8],
+}
+
+fn borrowed_from(value: &[u8], input: &[u8]) -> bool {
+    input.as_ptr_range().contains(&value.as_ptr()) && value.as_ptr_range().end <= input.as_ptr_range().end
+}
+
+#[test]
+fn borrows_input() {
+    let value = Frame { name: "dev", text: "ok".into(), vendor: "Ab".into(), payload: &[1, 2, 3] };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, *b"\x03devok\0Ab\0\x01\x02\x03");
+
+    let (frame, consumed) = Frame::from_bytes(&bytes).unwrap();
+    assert_eq!(frame, value);
+    assert_eq!(consumed, bytes.len());
+
+    assert!(borrowed_from(frame.name.as_bytes(), &bytes));
+    assert!(borrowed_from(frame.payload, &bytes));
+    assert!(matches!(&frame.text, Cow::Borrowed(text) if borrowed_from(text.as_bytes(), &bytes)));
+}
+
+#[test]
+fn owned_when_decoded() {
+    // latin1 characters above 0x7f are converted to UTF-8
+    let bytes = b"\x03devok\0M\xfcller\0";
+    let (frame, _) = Frame::from_bytes(bytes).unwrap();
+    assert_eq!(frame.vendor, "Müller");
+    assert!(matches!(frame.vendor, Cow::Owned(_)));
+    assert!(frame.payload.is_empty());
+}
+
+#[test]
+fn invalid_utf8() {
+    let bytes = b"\x02\xc3\x28ok\0Ab\0";
+    assert_eq!(Frame::from_bytes(bytes).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    let bytes = b"\x03dev\xffk\0Ab\0";
+    assert_eq!(Frame::from_bytes(bytes).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // the prefix exceeds the input
+    let bytes = b"\x09dev";
+    assert_eq!(Frame::from_bytes(bytes).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
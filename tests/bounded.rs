@@ -0,0 +1,97 @@
+mod common;
+
+pub use common::datatypes;
+use common::{deserialize, serialize};
+use datatypes::{Deserialize, Serialize};
+use sdk_macro::datatype;
+
+#[datatype]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Time {
+    seconds: u8,
+    minutes: u16,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Bounded {
+    a: heapless::Vec<u16, 4>,
+    b: arrayvec::ArrayVec<Time, 2>,
+    #[field(string = prefixed(u8))]
+    d: heapless::String<5>,
+    #[field(byte_len_ty = u8)]
+    e: heapless::Vec<Time, 3>,
+    #[field(string = fixed(6), encoding = latin1)]
+    f: heapless::String<8>,
+    #[field(until_eof)]
+    g: heapless::Vec<u8, 2>,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Unbounded {
+    a: Vec<u16>,
+    b: Vec<Time>,
+    #[field(string = prefixed(u8))]
+    d: String,
+    #[field(byte_len_ty = u8)]
+    e: Vec<Time>,
+    #[field(string = fixed(6), encoding = latin1)]
+    f: String,
+    #[field(until_eof)]
+    g: Vec<u8>,
+}
+
+fn unbounded() -> Unbounded {
+    Unbounded {
+        a: vec![1, 2, 3],
+        b: vec![Time { seconds: 1, minutes: 2 }],
+        d: "héll".into(),
+        e: vec![Time { seconds: 3, minutes: 4 }, Time { seconds: 5, minutes: 6 }],
+        f: "café".into(),
+        g: vec![9],
+    }
+}
+
+fn capacity_error(value: &Unbounded) -> String {
+    deserialize::<Bounded>(&serialize(value).unwrap()).unwrap_err().to_string()
+}
+
+#[test]
+fn same_wire_format() {
+    let value = Bounded {
+        a: heapless::Vec::from_slice(&[1, 2, 3]).unwrap(),
+        b: [Time { seconds: 1, minutes: 2 }].into_iter().collect(),
+        d: "héll".try_into().unwrap(),
+        e: heapless::Vec::from_slice(&unbounded().e).unwrap(),
+        f: "café".try_into().unwrap(),
+        g: heapless::Vec::from_slice(&[9]).unwrap(),
+    };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, serialize(&unbounded()).unwrap());
+    assert_eq!(deserialize::<Bounded>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn capacity() {
+    let mut value = unbounded();
+    value.a.push(4);
+    assert_eq!(deserialize::<Bounded>(&serialize(&value).unwrap()).unwrap().a.len(), 4);
+    value.a.push(5);
+    assert_eq!(capacity_error(&value), "'a' exceeds its capacity");
+
+    let mut value = unbounded();
+    value.b.push(Time { seconds: 0, minutes: 0 });
+    value.b.push(Time { seconds: 0, minutes: 0 });
+    assert_eq!(capacity_error(&value), "'b' exceeds its capacity");
+
+    let value = Unbounded { d: "héllo".into(), ..unbounded() };
+    assert_eq!(capacity_error(&value), "String 'd' exceeds its capacity");
+
+    let mut value = unbounded();
+    value.e.extend(value.e.clone());
+    assert_eq!(capacity_error(&value), "'e' exceeds its capacity");
+
+    let value = Unbounded { g: vec![1, 2, 3], ..unbounded() };
+    assert_eq!(capacity_error(&value), "'g' exceeds its capacity");
+}
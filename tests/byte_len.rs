@@ -0,0 +1,58 @@
+mod common;
+
+pub use common::datatypes;
+use common::{deserialize, serialize};
+use datatypes::{Deserialize, Serialize};
+use sdk_macro::datatype;
+use std::io::ErrorKind;
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Chunk {
+    id: u8,
+    #[field(string = prefixed(u8))]
+    name: String,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Entry {
+    value: u16,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Container {
+    #[field(byte_len_ty = u32, endianness = LittleEndian)]
+    chunks: Vec<Chunk>,
+    payload_size: u16,
+    #[field(byte_len = payload_size)]
+    payload: Vec<Entry>,
+}
+
+#[test]
+fn round_trip() {
+    let value = Container {
+        chunks: vec![Chunk { id: 1, name: "ab".into() }, Chunk { id: 2, name: "".into() }],
+        payload_size: 4,
+        payload: vec![Entry { value: 0x0102 }, Entry { value: 0x0304 }],
+    };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, [6, 0, 0, 0, 1, 2, b'a', b'b', 2, 0, 0, 4, 1, 2, 3, 4]);
+    assert_eq!(deserialize::<Container>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn mid_element() {
+    // the byte length ends within the second chunk
+    let bytes = [5, 0, 0, 0, 1, 2, b'a', b'b', 2, 0, 0, 0];
+    assert_eq!(deserialize::<Container>(&bytes).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // three bytes cannot hold entries of two bytes
+    let bytes = [0, 0, 0, 0, 0, 3, 1, 2, 3];
+    assert_eq!(deserialize::<Container>(&bytes).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // the byte length exceeds the input
+    let bytes = [0, 0, 0, 0, 0, 4, 1, 2];
+    assert_eq!(deserialize::<Container>(&bytes).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
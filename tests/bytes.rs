@@ -0,0 +1,139 @@
+mod common;
+
+pub use common::datatypes;
+use common::serialize;
+use datatypes::{Deserialize, Serialize, WriteTo};
+use sdk_macro::{data_enum, datatype};
+use std::io::ErrorKind;
+
+// type with hand-written (de-)serialization nested in generated datatypes
+#[derive(Debug, PartialEq)]
+pub struct Custom(u8);
+
+impl WriteTo for Custom {
+    fn write_to<W: std::io::Write + std::io::Seek>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&[self.0])
+    }
+}
+
+impl Serialize for Custom {
+    fn serialize(&self, writer: &mut std::io::BufWriter<std::fs::File>) -> std::io::Result<()> {
+        self.write_to(writer)
+    }
+
+    fn size(&self) -> u32 {
+        1
+    }
+}
+
+impl Deserialize for Custom {
+    fn deserialize(byte_stream: &mut std::io::Cursor<&[u8]>) -> std::io::Result<Self> {
+        let mut byte = [0u8];
+        std::io::Read::read_exact(byte_stream, &mut byte)?;
+        Ok(Custom(byte[0]))
+    }
+}
+
+#[data_enum(ty = u16)]
+pub enum Kind {
+    A = 1,
+    B = 2,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Inner {
+    a: u8,
+    #[field(string = prefixed(u8))]
+    name: String,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Message {
+    kind: Kind,
+    inner: Inner,
+    #[field(length_ty = u8)]
+    entries: Vec<Inner>,
+    #[field(checksum = crc32)]
+    crc: u32,
+    end: u8,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Handwritten {
+    custom: Custom,
+    #[field(length_ty = u8)]
+    customs: Vec<Custom>,
+    inner: Inner,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct TimedMessage {
+    seconds: u8,
+    #[field(length_ty = u8)]
+    messages: Vec<String>,
+    #[field(length_ty = u8, string = prefixed(u8), encoding = latin1)]
+    names: Vec<String>,
+}
+
+fn message() -> Message {
+    Message {
+        kind: Kind::B,
+        inner: Inner { a: 1, name: "ab".into() },
+        entries: vec![Inner { a: 2, name: "".into() }, Inner { a: 3, name: "c".into() }],
+        end: 4,
+    }
+}
+
+#[test]
+fn helpers() {
+    let value = message();
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(value.to_bytes(), bytes);
+
+    let mut buffer = [0u8; 64];
+    let written = value.serialize_into(&mut buffer).unwrap();
+    assert_eq!(&buffer[..written], &bytes[..]);
+    assert_eq!(Message::from_bytes(&buffer).unwrap(), (message(), written));
+
+    assert_eq!(Kind::B.to_bytes(), [0, 2]);
+    assert_eq!(Kind::from_bytes(&[0, 1, 9]).unwrap(), (Kind::A, 2));
+}
+
+#[test]
+fn too_small() {
+    let mut buffer = [0u8; 10];
+    assert_eq!(message().serialize_into(&mut buffer).unwrap_err().kind(), ErrorKind::WriteZero);
+    assert_eq!(Message::from_bytes(&[0, 2, 1]).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn handwritten() {
+    let value = Handwritten { custom: Custom(9), customs: vec![Custom(3), Custom(4)], inner: Inner { a: 1, name: "x".into() } };
+
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, [9, 2, 3, 4, 1, 1, b'x']);
+    assert_eq!(value.to_bytes(), bytes);
+
+    let mut buffer = [0u8; 7];
+    assert_eq!(value.serialize_into(&mut buffer).unwrap(), 7);
+    assert_eq!(Handwritten::from_bytes(&buffer).unwrap(), (value, 7));
+}
+
+#[test]
+fn strings() {
+    let value = TimedMessage { seconds: 1, messages: vec!["ab".into(), "".into()], names: vec!["é".into()] };
+
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, [1, 2, b'a', b'b', 0, 0, 1, 1, 0xe9]);
+    assert_eq!(value.to_bytes(), bytes);
+    assert_eq!(TimedMessage::from_bytes(&bytes).unwrap(), (value, 9));
+
+    // the errors of the elements are the ones of a String field
+    assert_eq!(TimedMessage::from_bytes(&[1, 1, b'a']).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    let value = TimedMessage { seconds: 1, messages: vec!["a\0".into()], names: vec![] };
+    assert_eq!(value.try_to_bytes().unwrap_err().kind(), ErrorKind::InvalidInput);
+}
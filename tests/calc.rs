@@ -0,0 +1,50 @@
+mod common;
+
+pub use common::datatypes;
+use common::{deserialize, serialize};
+use sdk_macro::datatype;
+use std::io::ErrorKind;
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Packet {
+    #[field(calc = self.payload.len() as u16 + 4, verify)]
+    total_length: u16,
+    #[field(calc = self.payload.len() as u8, omit)]
+    count: u8,
+    #[field(calc = self.payload.len() as f32 / 2.0)]
+    half_len: f32,
+    #[field(count = count)]
+    payload: Vec<u8>,
+}
+
+#[test]
+fn overwrites_value() {
+    let value = Packet { total_length: 0, half_len: -1.0, payload: vec![1, 2, 3] };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, [0, 7, 3, 0x3f, 0xc0, 0, 0, 1, 2, 3]);
+
+    let value = deserialize::<Packet>(&bytes).unwrap();
+    assert_eq!(value, Packet { total_length: 7, half_len: 1.5, payload: vec![1, 2, 3] });
+}
+
+#[test]
+fn omitted() {
+    // the omitted count is written and drives the deserialization of the payload
+    let value = Packet { total_length: 4, half_len: 0.0, payload: vec![] };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, [0, 4, 0, 0, 0, 0, 0]);
+    assert_eq!(deserialize::<Packet>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn verify_mismatch() {
+    let mut bytes = serialize(&Packet { total_length: 0, half_len: 0.0, payload: vec![1] }).unwrap();
+    bytes[1] = 4;
+    assert_eq!(deserialize::<Packet>(&bytes).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // fields without verify are taken as they are
+    let mut bytes = serialize(&Packet { total_length: 0, half_len: 0.0, payload: vec![1] }).unwrap();
+    bytes[3] = 0x40;
+    assert_eq!(deserialize::<Packet>(&bytes).unwrap().half_len, 2.0);
+}
@@ -0,0 +1,107 @@
+mod common;
+
+pub use common::datatypes;
+use common::{deserialize, serialize};
+use datatypes::Deserialize;
+use sdk_macro::datatype;
+
+pub struct LenSum;
+
+impl datatypes::Checksum for LenSum {
+    fn checksum(data: &[u8]) -> u64 {
+        data.len() as u64 + 0x100
+    }
+}
+
+#[datatype(magic = b"1234")]
+#[derive(Debug, PartialEq)]
+pub struct Frame {
+    a: u8,
+    b: u32,
+    #[field(checksum = crc32)]
+    crc: u32,
+    #[field(checksum = crc16_ccitt, over = (..=b))]
+    crc16: u16,
+    #[field(checksum = crc16_xmodem, over = (..crc), endianness = LittleEndian)]
+    xmodem: u16,
+    #[field(checksum = sum8, over = (a..=b))]
+    sum: u8,
+    #[field(checksum = xor8, over = (a..crc))]
+    xor: u8,
+    #[field(checksum = LenSum, over = (a..))]
+    custom: u8,
+    #[field(until_eof)]
+    tail: Vec<u8>,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Inner {
+    id: u8,
+    #[field(string = prefixed(u8))]
+    name: String,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Nested {
+    inner: Inner,
+    #[field(length_ty = u8)]
+    entries: Vec<Inner>,
+    #[field(checksum = crc32)]
+    crc: u32,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Flat {
+    #[field(count = 8)]
+    data: Vec<u8>,
+    #[field(checksum = crc32)]
+    crc: u32,
+}
+
+#[test]
+fn check_values() {
+    // the payload is the usual check input "123456789"
+    let value = Frame { a: b'5', b: 0x36373839, tail: vec![1, 2] };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(&bytes[..9], b"123456789");
+    assert_eq!(&bytes[9..13], &0xCBF43926u32.to_be_bytes());
+    assert_eq!(&bytes[13..15], &0x29B1u16.to_be_bytes());
+    assert_eq!(&bytes[15..17], &0x31C3u16.to_le_bytes());
+
+    let sum = b"56789".iter().fold(0u8, |sum, x| sum.wrapping_add(*x));
+    let xor = b"56789".iter().fold(0u8, |xor, x| xor ^ *x);
+    assert_eq!(&bytes[17..], &[sum, xor, 15, 1, 2]);
+    assert_eq!(deserialize::<Frame>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn mismatch() {
+    let bytes = serialize(&Frame { a: b'5', b: 0x36373839, tail: vec![] }).unwrap();
+    let mut corrupt = bytes.clone();
+    corrupt[5] ^= 1;
+    let error = deserialize::<Frame>(&corrupt).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    assert!(error.to_string().starts_with("Checksum mismatch for 'crc'"), "{}", error);
+
+    let mut corrupt = bytes.clone();
+    corrupt[19] ^= 1;
+    assert!(deserialize::<Frame>(&corrupt).unwrap_err().to_string().starts_with("Checksum mismatch for 'custom'"));
+}
+
+#[test]
+fn nested() {
+    let value = Nested {
+        inner: Inner { id: 1, name: "a".into() },
+        entries: vec![Inner { id: 2, name: "bc".into() }],
+    };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(value.to_bytes(), bytes);
+
+    // nested datatypes are covered by the checksum like any other field
+    let flat = serialize(&Flat { data: vec![1, 1, b'a', 1, 2, 2, b'b', b'c'] }).unwrap();
+    assert_eq!(bytes, flat);
+    assert_eq!(deserialize::<Nested>(&bytes).unwrap(), value);
+}
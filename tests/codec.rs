@@ -0,0 +1,76 @@
+mod common;
+
+pub use common::datatypes;
+use futures::{SinkExt, StreamExt};
+use sdk_macro::datatype;
+use tokio_util::codec::{Decoder, Encoder, FramedRead, FramedWrite};
+
+#[datatype(codec)]
+#[derive(Debug, PartialEq)]
+pub struct Chat {
+    id: u16,
+    #[field(string = prefixed(u8))]
+    text: String,
+}
+
+#[datatype(codec(length_ty = u16, max_frame_size = 16))]
+#[derive(Debug, PartialEq)]
+pub struct Blob {
+    #[field(until_eof)]
+    data: Vec<u8>,
+}
+
+#[tokio::test]
+async fn duplex() {
+    // frames are split across several reads by the small buffer
+    let (client, server) = tokio::io::duplex(3);
+    let mut sink = FramedWrite::new(client, ChatCodec::new());
+    let mut stream = FramedRead::new(server, ChatCodec::new());
+
+    let writer = async {
+        sink.send(Chat { id: 1, text: "hello".into() }).await.unwrap();
+        sink.send(Chat { id: 2, text: "".into() }).await.unwrap();
+        drop(sink);
+    };
+    let reader = async {
+        assert_eq!(stream.next().await.unwrap().unwrap(), Chat { id: 1, text: "hello".into() });
+        assert_eq!(stream.next().await.unwrap().unwrap(), Chat { id: 2, text: "".into() });
+        assert!(stream.next().await.is_none());
+    };
+    tokio::join!(writer, reader);
+}
+
+#[tokio::test]
+async fn length_delimited() {
+    let (client, server) = tokio::io::duplex(5);
+    let mut sink = FramedWrite::new(client, BlobCodec::new());
+    let mut stream = FramedRead::new(server, BlobCodec::new());
+
+    let writer = async {
+        sink.send(Blob { data: vec![1, 2, 3] }).await.unwrap();
+        sink.send(Blob { data: vec![] }).await.unwrap();
+    };
+    let reader = async {
+        assert_eq!(stream.next().await.unwrap().unwrap(), Blob { data: vec![1, 2, 3] });
+        assert_eq!(stream.next().await.unwrap().unwrap(), Blob { data: vec![] });
+    };
+    tokio::join!(writer, reader);
+}
+
+#[test]
+fn frame_limits() {
+    let mut codec = BlobCodec::new();
+    let mut buffer = bytes::BytesMut::new();
+    assert!(codec.encode(Blob { data: vec![0; 17] }, &mut buffer).is_err());
+    buffer.extend_from_slice(&[0, 17, 1]);
+    assert!(codec.decode(&mut buffer).is_err());
+
+    let mut codec = ChatCodec::with_max_frame_size(4);
+    let mut buffer = bytes::BytesMut::from(&[0u8, 1, 200, b'a'][..]);
+    assert!(codec.decode(&mut buffer).is_err());
+
+    // incomplete frames are left in the buffer
+    let mut buffer = bytes::BytesMut::from(&[0u8, 1][..]);
+    assert_eq!(codec.decode(&mut buffer).unwrap(), None);
+    assert_eq!(buffer.len(), 2);
+}
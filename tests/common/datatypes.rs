@@ -0,0 +1,28 @@
+// runtime the generated code refers to as `crate::datatypes`, every test re-exports it at its root and
+// the documentation examples include it
+pub trait Serialize {
+    fn serialize(&self, writer: &mut std::io::BufWriter<std::fs::File>) -> std::io::Result<()>;
+    fn size(&self) -> u32;
+}
+
+pub trait WriteTo {
+    fn write_to<W: std::io::Write + std::io::Seek>(&self, writer: &mut W) -> std::io::Result<()>;
+}
+
+pub trait Deserialize {
+    fn deserialize(byte_stream: &mut std::io::Cursor<&[u8]>) -> std::io::Result<Self> where Self: Sized;
+}
+
+pub trait Checksum {
+    fn checksum(data: &[u8]) -> u64;
+}
+
+pub trait FixedSize {
+    const SIZE: usize;
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Decoded<T> {
+    Complete(T, usize),
+    Incomplete { needed: Option<usize> },
+}
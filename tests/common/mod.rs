@@ -0,0 +1,37 @@
+#![allow(dead_code)]
+
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub mod datatypes;
+
+static FILES: AtomicUsize = AtomicUsize::new(0);
+
+// serializes through Serialize::serialize, which needs a file, and checks the result against size()
+pub fn serialize<T: datatypes::Serialize>(value: &T) -> std::io::Result<Vec<u8>> {
+    let bytes = serialize_after(&[], value)?;
+    assert_eq!(bytes.len() as u32, value.size(), "size() does not match the serialized bytes");
+    Ok(bytes)
+}
+
+// serializes behind `prefix` for datatypes depending on their position in the stream, the returned
+// bytes include the prefix
+pub fn serialize_after<T: datatypes::Serialize>(prefix: &[u8], value: &T) -> std::io::Result<Vec<u8>> {
+    let path = std::env::temp_dir().join(format!("sdk_macro_{}_{}", std::process::id(), FILES.fetch_add(1, Ordering::Relaxed)));
+    let file = std::fs::OpenOptions::new().read(true).write(true).create(true).truncate(true).open(&path)?;
+    let mut writer = std::io::BufWriter::new(file);
+    let result = writer.write_all(prefix).and_then(|_| value.serialize(&mut writer)).and_then(|_| writer.flush());
+
+    let mut bytes = vec![];
+    let mut file = writer.into_inner().map_err(|e| e.into_error())?;
+    file.seek(SeekFrom::Start(0))?;
+    file.read_to_end(&mut bytes)?;
+    std::fs::remove_file(&path)?;
+
+    result?;
+    Ok(bytes)
+}
+
+pub fn deserialize<T: datatypes::Deserialize>(bytes: &[u8]) -> std::io::Result<T> {
+    T::deserialize(&mut std::io::Cursor::new(bytes))
+}
@@ -0,0 +1,69 @@
+mod common;
+
+pub use common::datatypes;
+use common::{deserialize, serialize};
+use sdk_macro::datatype;
+use std::io::ErrorKind;
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Time {
+    #[field(range = 0..24)]
+    hours: u8,
+    #[field(range = 0..=59, assert = self.minutes < 30 || self.hours > 0)]
+    minutes: u8,
+    #[field(one_of = [1, 2, 4])]
+    factor: u16,
+    #[field(range = -1.0..=1.0)]
+    level: f32,
+    #[field(one_of = ['a', 'b'])]
+    mode: char,
+}
+
+fn time() -> Time {
+    Time { hours: 23, minutes: 59, factor: 4, level: 0.5, mode: 'b' }
+}
+
+fn rejected(value: Time) {
+    let error = value.validate().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+    // serialization does not check the constraints, deserialization does
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(deserialize::<Time>(&bytes).unwrap_err().kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn accepted() {
+    let value = time();
+    value.validate().unwrap();
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(deserialize::<Time>(&bytes).unwrap(), value);
+
+    let value = Time { hours: 0, minutes: 15, factor: 1, level: -1.0, mode: 'a' };
+    value.validate().unwrap();
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(deserialize::<Time>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn range() {
+    rejected(Time { hours: 24, ..time() });
+    rejected(Time { minutes: 60, ..time() });
+    rejected(Time { level: 1.5, ..time() });
+    rejected(Time { level: f32::NAN, ..time() });
+}
+
+#[test]
+fn one_of() {
+    rejected(Time { factor: 3, ..time() });
+    rejected(Time { mode: 'c', ..time() });
+}
+
+#[test]
+fn assert() {
+    rejected(Time { hours: 0, minutes: 40, ..time() });
+
+    let error = Time { hours: 0, minutes: 40, ..time() }.validate().unwrap_err();
+    assert!(error.to_string().contains("'minutes'"), "{}", error);
+}
@@ -0,0 +1,61 @@
+mod common;
+
+pub use common::datatypes;
+use common::{deserialize, serialize};
+use sdk_macro::datatype;
+use std::io::ErrorKind;
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Entry {
+    value: u16,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Log {
+    num_entries: u8,
+    flags: u8,
+    #[field(count = num_entries)]
+    entries: Vec<Entry>,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Pairs {
+    num_pairs: u8,
+    #[field(count = num_pairs as usize * 2)]
+    values: Vec<Entry>,
+}
+
+fn entries(values: &[u16]) -> Vec<Entry> {
+    values.iter().map(|value| Entry { value: *value }).collect()
+}
+
+#[test]
+fn round_trip() {
+    let value = Log { num_entries: 2, flags: 7, entries: entries(&[1, 0x0203]) };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, [2, 7, 0, 1, 2, 3]);
+    assert_eq!(deserialize::<Log>(&bytes).unwrap(), value);
+
+    let value = Pairs { num_pairs: 1, values: entries(&[1, 2]) };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, [1, 0, 1, 0, 2]);
+    assert_eq!(deserialize::<Pairs>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn field_disagrees() {
+    // the count field is written from the length of the collection
+    let bytes = serialize(&Log { num_entries: 9, flags: 0, entries: entries(&[5]) }).unwrap();
+    assert_eq!(bytes, [1, 0, 0, 5]);
+    assert_eq!(deserialize::<Log>(&bytes).unwrap().num_entries, 1);
+
+    let error = serialize(&Log { num_entries: 0, flags: 0, entries: entries(&[0; 256]) }).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+
+    // the count decides how many elements are read
+    assert_eq!(deserialize::<Log>(&[1, 0, 0, 5, 0, 6]).unwrap().entries, entries(&[5]));
+    assert_eq!(deserialize::<Log>(&[3, 0, 0, 5, 0, 6]).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
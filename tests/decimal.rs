@@ -0,0 +1,101 @@
+mod common;
+
+pub use common::datatypes;
+use common::{deserialize, serialize};
+use sdk_macro::datatype;
+use std::io::ErrorKind;
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct TimedMessage {
+    #[field(bcd)]
+    hours: u8,
+    #[field(bcd)]
+    minutes: u8,
+    #[field(ascii_decimal(width = 6))]
+    id: u32,
+    #[field(bcd(width = 3), endianness = LittleEndian)]
+    date: u32,
+    #[field(ascii_decimal)]
+    offset: i16,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Wide {
+    #[field(bcd)]
+    packed: u128,
+    #[field(ascii_decimal)]
+    text: u128,
+}
+
+fn message() -> TimedMessage {
+    TimedMessage { hours: 23, minutes: 59, id: 123, date: 191026, offset: 120 }
+}
+
+#[test]
+fn round_trip() {
+    let value = message();
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, *b"\x23\x59000123\x26\x10\x1900120");
+    assert_eq!(deserialize::<TimedMessage>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn maximum_width() {
+    // 16 bytes hold 32 BCD digits, 39 ASCII digits hold every u128
+    let value = Wide { packed: 10u128.pow(32) - 1, text: u128::MAX };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(&bytes[..16], &[0x99; 16]);
+    assert_eq!(&bytes[16..], u128::MAX.to_string().as_bytes());
+    assert_eq!(deserialize::<Wide>(&bytes).unwrap(), value);
+
+    let value = Wide { packed: 0, text: 0 };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(&bytes[..16], &[0; 16]);
+    assert_eq!(&bytes[16..], &[b'0'; 39]);
+    assert_eq!(deserialize::<Wide>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn invalid_digits() {
+    let bytes = serialize(&message()).unwrap();
+
+    // nibbles above 9
+    for (index, byte) in [(0, 0x2a), (1, 0xa9), (8, 0x0f)] {
+        let mut bytes = bytes.clone();
+        bytes[index] = byte;
+        assert_eq!(deserialize::<TimedMessage>(&bytes).unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    // ASCII characters that are not digits, including signs and spaces
+    for (index, byte) in [(2, b' '), (7, b'a'), (11, b'-'), (15, b'+')] {
+        let mut bytes = bytes.clone();
+        bytes[index] = byte;
+        assert_eq!(deserialize::<TimedMessage>(&bytes).unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+}
+
+#[test]
+fn overflow() {
+    // too many digits or negative values fail the serialization
+    for value in [
+        TimedMessage { id: 1_000_000, ..message() },
+        TimedMessage { date: 1_000_000, ..message() },
+        TimedMessage { hours: 100, ..message() },
+        TimedMessage { offset: -1, ..message() },
+    ] {
+        assert_eq!(serialize(&value).unwrap_err().kind(), ErrorKind::InvalidInput, "{:?}", value);
+    }
+    let error = serialize(&Wide { packed: 10u128.pow(32), text: 0 }).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+
+    // valid digits whose value does not fit into the integer
+    let mut bytes = serialize(&message()).unwrap();
+    bytes[11..16].copy_from_slice(b"99999");
+    assert_eq!(deserialize::<TimedMessage>(&bytes).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    let mut bytes = serialize(&Wide { packed: 0, text: 0 }).unwrap();
+    bytes[16] = b'4';
+    assert_eq!(deserialize::<Wide>(&bytes).unwrap_err().kind(), ErrorKind::InvalidData);
+}
@@ -0,0 +1,66 @@
+mod common;
+
+pub use common::datatypes;
+use datatypes::Decoded;
+use sdk_macro::datatype;
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Packet {
+    kind: u8,
+    #[field(length_ty = u16)]
+    payload: Vec<u16>,
+    #[field(string = terminated)]
+    note: String,
+    #[field(checksum = crc32)]
+    crc: u32,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Time {
+    seconds: u8,
+    minutes: u16,
+}
+
+fn packet() -> Packet {
+    Packet { kind: 1, payload: vec![1, 2, 3], note: "hi".into() }
+}
+
+#[test]
+fn needed() {
+    let bytes = packet().to_bytes();
+    assert_eq!(bytes.len(), 16);
+    assert_eq!(Packet::decode(&[]).unwrap(), Decoded::Incomplete { needed: Some(1) });
+    assert_eq!(Packet::decode(&bytes[..2]).unwrap(), Decoded::Incomplete { needed: Some(1) });
+    assert_eq!(Packet::decode(&bytes[..4]).unwrap(), Decoded::Incomplete { needed: Some(5) });
+    assert_eq!(Packet::decode(&bytes[..10]).unwrap(), Decoded::Incomplete { needed: None });
+    assert_eq!(Packet::decode(&bytes[..13]).unwrap(), Decoded::Incomplete { needed: Some(3) });
+    assert_eq!(Time::decode(&[1]).unwrap(), Decoded::Incomplete { needed: Some(2) });
+
+    let mut extended = bytes.clone();
+    extended.push(0xaa);
+    assert_eq!(Packet::decode(&extended).unwrap(), Decoded::Complete(packet(), 16));
+
+    let mut corrupt = bytes.clone();
+    corrupt[15] ^= 1;
+    assert!(Packet::decode(&corrupt).is_err());
+}
+
+#[test]
+fn read_loop() {
+    let bytes = packet().to_bytes();
+    let stream = [bytes.clone(), bytes].concat();
+
+    let mut buffer = vec![];
+    let mut decoded = vec![];
+    for chunk in stream.chunks(5) {
+        buffer.extend_from_slice(chunk);
+        while let Decoded::Complete(value, consumed) = Packet::decode(&buffer).unwrap() {
+            decoded.push(value);
+            buffer.drain(..consumed);
+        }
+    }
+    assert_eq!(decoded, [packet(), packet()]);
+    assert!(buffer.is_empty());
+}
@@ -0,0 +1,104 @@
+mod common;
+
+pub use common::datatypes;
+use common::{deserialize, serialize};
+use sdk_macro::datatype;
+use std::io::ErrorKind;
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Device {
+    #[field(encoding = latin1)]
+    vendor: String,
+    #[field(encoding = windows1252, string = prefixed(u8))]
+    price: String,
+    #[field(encoding = utf16le)]
+    name: String,
+    #[field(encoding = utf16be, string = fixed(6))]
+    model: String,
+    #[field(encoding = latin1)]
+    unit: char,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Lossy {
+    #[field(encoding = ascii, lossy)]
+    text: String,
+    #[field(encoding = windows1252, lossy, string = prefixed(u8))]
+    symbols: String,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Ascii {
+    #[field(encoding = ascii)]
+    text: String,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Units {
+    #[field(encoding = utf16le, string = prefixed(u8), length_unit = code_units)]
+    units: String,
+    #[field(encoding = utf16le, string = prefixed(u8))]
+    bytes: String,
+}
+
+fn device() -> Device {
+    Device { vendor: "Müller".into(), price: "5€".into(), name: "é€".into(), model: "ñ".into(), unit: 'µ' }
+}
+
+#[test]
+fn round_trip() {
+    let value = device();
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, [
+        b'M', 0xfc, b'l', b'l', b'e', b'r', 0,
+        2, b'5', 0x80,
+        0xe9, 0x00, 0xac, 0x20, 0, 0,
+        0x00, 0xf1, 0, 0, 0, 0,
+        0xb5,
+    ]);
+    assert_eq!(deserialize::<Device>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn unencodable() {
+    let error = serialize(&Device { vendor: "€".into(), ..device() }).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    assert_eq!(serialize(&Device { unit: 'あ', ..device() }).unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    // ascii only has seven bits
+    assert_eq!(deserialize::<Ascii>(&[0xe9, 0]).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // unpaired surrogate
+    let mut bytes = serialize(&device()).unwrap();
+    bytes[10..12].copy_from_slice(&[0x00, 0xd8]);
+    assert_eq!(deserialize::<Device>(&bytes).unwrap_err().kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn lossy() {
+    let bytes = serialize(&Lossy { text: "aé".into(), symbols: "€あ".into() }).unwrap();
+    assert_eq!(bytes, [b'a', b'?', 0, 2, 0x80, b'?']);
+
+    // undefined windows-1252 bytes are C1 control characters
+    let value = deserialize::<Lossy>(&[b'a', 0xe9, 0, 2, 0x80, 0x81]).unwrap();
+    assert_eq!(value, Lossy { text: "a\u{fffd}".into(), symbols: "€\u{81}".into() });
+}
+
+#[test]
+fn length_unit() {
+    // the emoji takes two UTF-16 code units, four bytes
+    let value = Units { units: "a😀".into(), bytes: "a😀".into() };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes[0], 3);
+    assert_eq!(bytes[7], 6);
+    assert_eq!(bytes.len(), 14);
+    assert_eq!(deserialize::<Units>(&bytes).unwrap(), value);
+
+    // a byte length must consist of whole code units
+    let bytes = [0, 3, b'a', 0, 0];
+    assert_eq!(deserialize::<Units>(&bytes).unwrap_err().kind(), ErrorKind::InvalidData);
+}
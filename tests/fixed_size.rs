@@ -0,0 +1,108 @@
+mod common;
+
+pub use common::datatypes;
+use common::{deserialize, serialize};
+use datatypes::{FixedSize, Serialize};
+use sdk_macro::datatype;
+
+#[datatype]
+#[derive(Debug, Default, PartialEq)]
+pub struct Time {
+    seconds: u8,
+    minutes: u8,
+    hours: u8,
+}
+
+#[datatype(fixed_size = 56)]
+#[derive(Debug, PartialEq)]
+pub struct TimedValue {
+    time: Time,
+    value: u32,
+}
+
+#[datatype(align = 4, fixed_size)]
+#[derive(Debug, PartialEq)]
+pub struct Record {
+    kind: u8,
+    #[field(align = 2, float = f16)]
+    level: f32,
+    #[field(string = fixed(5))]
+    name: String,
+    #[field(count = 2)]
+    values: [Time; 2],
+    #[field(bcd)]
+    id: u16,
+}
+
+// hand-written type whose SIZE does not match what it writes
+#[derive(Debug, PartialEq)]
+pub struct Wrong(u8);
+
+impl datatypes::WriteTo for Wrong {
+    fn write_to<W: std::io::Write + std::io::Seek>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&[self.0, self.0])
+    }
+}
+
+impl datatypes::Serialize for Wrong {
+    fn serialize(&self, writer: &mut std::io::BufWriter<std::fs::File>) -> std::io::Result<()> {
+        datatypes::WriteTo::write_to(self, writer)
+    }
+
+    fn size(&self) -> u32 {
+        2
+    }
+}
+
+impl datatypes::Deserialize for Wrong {
+    fn deserialize(byte_stream: &mut std::io::Cursor<&[u8]>) -> std::io::Result<Self> {
+        let mut bytes = [0u8; 2];
+        std::io::Read::read_exact(byte_stream, &mut bytes)?;
+        Ok(Wrong(bytes[0]))
+    }
+}
+
+impl FixedSize for Wrong {
+    const SIZE: usize = 1;
+}
+
+#[datatype(fixed_size)]
+#[derive(Debug, PartialEq)]
+pub struct Outer {
+    kind: u8,
+    wrong: Wrong,
+}
+
+#[test]
+fn size() {
+    assert_eq!(Time::SIZE, 3);
+    assert_eq!(TimedValue::SIZE, 7);
+    assert_eq!(<Record as FixedSize>::SIZE, 20);
+
+    let value = TimedValue { time: Time { seconds: 1, minutes: 2, hours: 3 }, value: 4 };
+    let mut buffer = [0u8; TimedValue::SIZE];
+    assert_eq!(value.serialize_into(&mut buffer).unwrap(), TimedValue::SIZE);
+    assert_eq!(value.size() as usize, TimedValue::SIZE);
+    assert_eq!(deserialize::<TimedValue>(&buffer).unwrap(), value);
+}
+
+#[test]
+fn matches_serialized_length() {
+    let time = Time { seconds: 1, minutes: 2, hours: 3 };
+    let value = Record { kind: 1, level: 0.5, name: "ab".into(), values: [time, Time { seconds: 4, minutes: 5, hours: 6 }], id: 42 };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes.len(), Record::SIZE);
+    assert_eq!(bytes, [1, 0, 0x38, 0, b'a', b'b', 0, 0, 0, 1, 2, 3, 4, 5, 6, 0x00, 0x42, 0, 0, 0]);
+}
+
+#[test]
+#[should_panic(expected = "'Outer' wrote a different amount of bytes than its SIZE")]
+fn wrong_nested_size() {
+    serialize(&Outer { kind: 1, wrong: Wrong(2) }).unwrap();
+}
+
+#[test]
+fn ui() {
+    let tests = trybuild::TestCases::new();
+    tests.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,145 @@
+mod common;
+
+pub use common::datatypes;
+use common::{deserialize, serialize};
+use sdk_macro::datatype;
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Record {
+    #[field(float = f16)]
+    half: f32,
+    #[field(float = bf16, endianness = LittleEndian)]
+    brain: f32,
+    #[field(float = ibm32)]
+    ibm: f64,
+    #[field(float = ibm64)]
+    ibm_double: f64,
+    #[field(float = vax_f)]
+    vax: f32,
+    #[field(float = vax_g)]
+    vax_double: f64,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Wide {
+    #[field(float = f16)]
+    half: f64,
+    #[field(float = bf16)]
+    brain: f64,
+}
+
+fn wide(value: f64) -> [u16; 2] {
+    let bytes = serialize(&Wide { half: value, brain: value }).unwrap();
+    [u16::from_be_bytes([bytes[0], bytes[1]]), u16::from_be_bytes([bytes[2], bytes[3]])]
+}
+
+fn record(half: f32, ibm: f64, vax: f64) -> Record {
+    Record { half, brain: half, ibm, ibm_double: ibm, vax: vax as f32, vax_double: vax }
+}
+
+fn half(value: f32) -> u16 {
+    let bytes = serialize(&record(value, 0.0, 0.0)).unwrap();
+    u16::from_be_bytes([bytes[0], bytes[1]])
+}
+
+fn ibm(value: f64) -> u32 {
+    let bytes = serialize(&record(0.0, value, 0.0)).unwrap();
+    u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]])
+}
+
+#[test]
+fn known_encodings() {
+    let value = record(1.0, 1.0, 1.0);
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, [
+        0x3c, 0x00,
+        0x80, 0x3f,
+        0x41, 0x10, 0, 0,
+        0x41, 0x10, 0, 0, 0, 0, 0, 0,
+        0x80, 0x40, 0, 0,
+        0x10, 0x40, 0, 0, 0, 0, 0, 0,
+    ]);
+    assert_eq!(deserialize::<Record>(&bytes).unwrap(), value);
+
+    // -118.625 is the example of the IBM hexadecimal float format
+    let value = record(-2.0, -118.625, -0.75);
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(&bytes[..2], &[0xc0, 0x00]);
+    assert_eq!(&bytes[4..8], &[0xc2, 0x76, 0xa0, 0x00]);
+    assert_eq!(deserialize::<Record>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn half_precision() {
+    assert_eq!(half(65504.0), 0x7bff);
+    assert_eq!(half(65520.0), 0x7c00);
+    assert_eq!(half(f32::INFINITY), 0x7c00);
+    assert_eq!(half(f32::NEG_INFINITY), 0xfc00);
+    assert_eq!(half(f32::NAN), 0x7e00);
+    assert_eq!(half(-0.0), 0x8000);
+
+    // subnormals and rounding to even
+    assert_eq!(half(2f32.powi(-24)), 0x0001);
+    assert_eq!(half(2f32.powi(-25)), 0x0000);
+    assert_eq!(half(2f32.powi(-25) * 1.5), 0x0001);
+    assert_eq!(half(2f32.powi(-14)), 0x0400);
+    assert_eq!(half(1.0 + 2f32.powi(-11)), 0x3c00);
+    assert_eq!(half(1.0 + 3.0 * 2f32.powi(-11)), 0x3c02);
+}
+
+#[test]
+fn from_f64() {
+    // slightly above the halfway point, a detour through f32 would round to the halfway point first
+    assert_eq!(wide(1.0 + 2f64.powi(-11) + 2f64.powi(-40))[0], 0x3c01);
+    assert_eq!(wide(1.0 + 2f64.powi(-8) + 2f64.powi(-40))[1], 0x3f81);
+    assert_eq!(wide(1.0 + 2f64.powi(-11)), [0x3c00, 0x3f80]);
+
+    assert_eq!(wide(1e300), [0x7c00, 0x7f80]);
+    assert_eq!(wide(-1e-300), [0x8000, 0x8000]);
+    assert_eq!(wide(2f64.powi(-133)), [0x0000, 0x0001]);
+    assert_eq!(wide(f64::NAN), [0x7e00, 0x7fc0]);
+}
+
+#[test]
+fn ibm_limits() {
+    assert_eq!(ibm(0.1), 0x4019999a);
+    assert_eq!(ibm(16f64.powi(-65)), 0x00100000);
+    assert_eq!(ibm(16f64.powi(-70)), 1);
+    assert_eq!(ibm(2f64.powi(-281)), 0);
+    assert_eq!(ibm(2f64.powi(-281) * 1.5), 1);
+
+    assert!(serialize(&record(0.0, f64::NAN, 0.0)).is_err());
+    assert!(serialize(&record(0.0, f64::INFINITY, 0.0)).is_err());
+    let error = serialize(&record(0.0, 1e80, 0.0)).unwrap_err();
+    assert_eq!(error.to_string(), "'ibm' 1e80 cannot be represented as ibm32");
+}
+
+#[test]
+fn vax_limits() {
+    let bytes = serialize(&record(f32::NAN, 0.0, f64::NAN)).unwrap();
+    assert_eq!(&bytes[16..20], &[0x00, 0x80, 0, 0]);
+    let value = deserialize::<Record>(&bytes).unwrap();
+    assert!(value.half.is_nan() && value.brain.is_nan() && value.vax.is_nan() && value.vax_double.is_nan());
+
+    assert!(serialize(&record(0.0, 0.0, f64::INFINITY)).is_err());
+    assert!(serialize(&record(0.0, 0.0, 1e300)).is_err());
+    let value = deserialize::<Record>(&serialize(&record(0.0, 0.0, 1e-310)).unwrap()).unwrap();
+    assert_eq!(value.vax_double, 0.0);
+}
+
+#[test]
+fn round_trips() {
+    for x in [0.1f32, 3.5, -1e-5, 6.1e-5, 1e-7, 65504.0] {
+        let value = deserialize::<Record>(&serialize(&record(x, x as f64, x as f64)).unwrap()).unwrap();
+        assert_eq!(value.ibm_double, x as f64);
+        assert_eq!(value.vax, x);
+        assert_eq!(value.vax_double, x as f64);
+        assert!((value.half - x).abs() <= x.abs() / 1000.0 + 6e-8, "{} {}", value.half, x);
+    }
+
+    let value = deserialize::<Record>(&serialize(&record(f32::INFINITY, 0.0, 0.0)).unwrap()).unwrap();
+    assert_eq!(value.half, f32::INFINITY);
+    assert_eq!(value.brain, f32::INFINITY);
+}
@@ -0,0 +1,34 @@
+mod common;
+
+pub use common::datatypes;
+use common::{deserialize, serialize};
+use sdk_macro::datatype;
+use std::io::ErrorKind;
+
+#[datatype(magic = b"RIFF")]
+#[derive(Debug, PartialEq)]
+pub struct Riff {
+    size: u32,
+    #[field(magic = 0xCAFEBABE, endianness = LittleEndian)]
+    _signature: u32,
+    #[field(magic = b"WAVE")]
+    _format: [u8; 4],
+}
+
+#[test]
+fn round_trip() {
+    let value = Riff { size: 16 };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, *b"RIFF\0\0\0\x10\xbe\xba\xfe\xcaWAVE");
+    assert_eq!(deserialize::<Riff>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn mismatch() {
+    let bytes = serialize(&Riff { size: 16 }).unwrap();
+    for index in [0, 8, 15] {
+        let mut bytes = bytes.clone();
+        bytes[index] ^= 1;
+        assert_eq!(deserialize::<Riff>(&bytes).unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,17 @@
+use std::process::Command;
+
+// the generated code of the no_std feature is checked by the #![no_std] crate in tests/no_std, it is
+// built separately so that the feature does not apply to the other tests
+#[test]
+fn no_std() {
+    let status = Command::new(env!("CARGO"))
+        .arg("test")
+        .arg("--manifest-path")
+        .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/no_std/Cargo.toml"))
+        .arg("--target-dir")
+        .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/target/no_std"))
+        .status()
+        .unwrap();
+
+    assert!(status.success());
+}
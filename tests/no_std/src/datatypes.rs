@@ -0,0 +1,234 @@
+pub trait Serialize {
+    fn serialize(&self, writer: &mut io::Writer) -> io::Result<()>;
+    fn size(&self) -> u32;
+}
+
+pub trait WriteTo {
+    fn write_to<W: io::Write + io::Seek>(&self, writer: &mut W) -> io::Result<()>;
+}
+
+pub trait Deserialize {
+    fn deserialize(byte_stream: &mut io::Cursor<&[u8]>) -> io::Result<Self> where Self: Sized;
+}
+
+pub trait Checksum {
+    fn checksum(data: &[u8]) -> u64;
+}
+
+pub trait FixedSize {
+    const SIZE: usize;
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Decoded<T> {
+    Complete(T, usize),
+    Incomplete { needed: Option<usize> },
+}
+
+// the parts of std::io and byteorder used by the generated code
+pub mod io {
+    use alloc::{borrow::Cow, vec::Vec};
+    use byteorder::ByteOrder;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ErrorKind {
+        InvalidData,
+        InvalidInput,
+        UnexpectedEof,
+        WriteZero,
+    }
+
+    #[derive(Debug)]
+    pub struct Error {
+        kind: ErrorKind,
+        message: Cow<'static, str>,
+    }
+
+    impl Error {
+        pub fn new(kind: ErrorKind, message: impl Into<Cow<'static, str>>) -> Self {
+            Error { kind, message: message.into() }
+        }
+
+        pub fn kind(&self) -> ErrorKind {
+            self.kind
+        }
+
+        pub fn message(&self) -> &str {
+            &self.message
+        }
+    }
+
+    impl From<ErrorKind> for Error {
+        fn from(kind: ErrorKind) -> Self {
+            Error::new(kind, "")
+        }
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    pub struct Cursor<T> {
+        inner: T,
+        pos: u64,
+    }
+
+    impl<T> Cursor<T> {
+        pub fn new(inner: T) -> Self {
+            Cursor { inner, pos: 0 }
+        }
+
+        pub fn position(&self) -> u64 {
+            self.pos
+        }
+
+        pub fn set_position(&mut self, pos: u64) {
+            self.pos = pos;
+        }
+
+        pub fn get_ref(&self) -> &T {
+            &self.inner
+        }
+
+        pub fn into_inner(self) -> T {
+            self.inner
+        }
+    }
+
+    pub type Writer = Cursor<Vec<u8>>;
+
+    pub trait Read {
+        fn read_exact(&mut self, buf: &mut [u8]) -> Result<()>;
+    }
+
+    pub trait BufRead: Read {
+        fn read_until(&mut self, byte: u8, buf: &mut Vec<u8>) -> Result<usize>;
+    }
+
+    pub trait Write {
+        fn write_all(&mut self, buf: &[u8]) -> Result<()>;
+    }
+
+    pub trait Seek {
+        fn stream_position(&mut self) -> Result<u64>;
+    }
+
+    impl<T> Seek for Cursor<T> {
+        fn stream_position(&mut self) -> Result<u64> {
+            Ok(self.pos)
+        }
+    }
+
+    impl<T: AsRef<[u8]>> Read for Cursor<T> {
+        fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+            let data = self.inner.as_ref();
+            let start = (self.pos as usize).min(data.len());
+            if data.len() - start < buf.len() {
+                self.pos = data.len() as u64;
+                return Err(ErrorKind::UnexpectedEof.into());
+            }
+
+            buf.copy_from_slice(&data[start..start + buf.len()]);
+            self.pos += buf.len() as u64;
+            Ok(())
+        }
+    }
+
+    impl<T: AsRef<[u8]>> BufRead for Cursor<T> {
+        fn read_until(&mut self, byte: u8, buf: &mut Vec<u8>) -> Result<usize> {
+            let data = self.inner.as_ref();
+            let start = (self.pos as usize).min(data.len());
+            let end = match data[start..].iter().position(|x| *x == byte) {
+                Some(index) => start + index + 1,
+                None => data.len(),
+            };
+
+            buf.extend_from_slice(&data[start..end]);
+            self.pos = end as u64;
+            Ok(end - start)
+        }
+    }
+
+    impl Write for Cursor<Vec<u8>> {
+        fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+            let start = self.pos as usize;
+            if self.inner.len() < start + buf.len() {
+                self.inner.resize(start + buf.len(), 0);
+            }
+
+            self.inner[start..start + buf.len()].copy_from_slice(buf);
+            self.pos += buf.len() as u64;
+            Ok(())
+        }
+    }
+
+    impl Write for Cursor<&mut [u8]> {
+        fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+            let start = (self.pos as usize).min(self.inner.len());
+            if self.inner.len() - start < buf.len() {
+                return Err(ErrorKind::WriteZero.into());
+            }
+
+            self.inner[start..start + buf.len()].copy_from_slice(buf);
+            self.pos += buf.len() as u64;
+            Ok(())
+        }
+    }
+
+    macro_rules! bytes_ext {
+        ($(($read:ident, $write:ident, $ty:ty)),*) => {
+            pub trait ReadBytesExt: Read {
+                fn read_u8(&mut self) -> Result<u8> {
+                    let mut buf = [0u8; 1];
+                    self.read_exact(&mut buf)?;
+                    Ok(buf[0])
+                }
+
+                fn read_i8(&mut self) -> Result<i8> {
+                    Ok(self.read_u8()? as i8)
+                }
+
+                $(
+                    fn $read<B: ByteOrder>(&mut self) -> Result<$ty> {
+                        let mut buf = [0u8; core::mem::size_of::<$ty>()];
+                        self.read_exact(&mut buf)?;
+                        Ok(B::$read(&buf))
+                    }
+                )*
+            }
+
+            impl<R: Read> ReadBytesExt for R {}
+
+            pub trait WriteBytesExt: Write {
+                fn write_u8(&mut self, value: u8) -> Result<()> {
+                    self.write_all(&[value])
+                }
+
+                fn write_i8(&mut self, value: i8) -> Result<()> {
+                    self.write_all(&[value as u8])
+                }
+
+                $(
+                    fn $write<B: ByteOrder>(&mut self, value: $ty) -> Result<()> {
+                        let mut buf = [0u8; core::mem::size_of::<$ty>()];
+                        B::$write(&mut buf, value);
+                        self.write_all(&buf)
+                    }
+                )*
+            }
+
+            impl<W: Write> WriteBytesExt for W {}
+        };
+    }
+
+    bytes_ext!(
+        (read_u16, write_u16, u16),
+        (read_u32, write_u32, u32),
+        (read_u64, write_u64, u64),
+        (read_u128, write_u128, u128),
+        (read_i16, write_i16, i16),
+        (read_i32, write_i32, i32),
+        (read_i64, write_i64, i64),
+        (read_i128, write_i128, i128),
+        (read_f32, write_f32, f32),
+        (read_f64, write_f64, f64)
+    );
+}
@@ -0,0 +1,100 @@
+#![no_std]
+
+// datatypes using the generated code without std, tests/ checks them with std available
+
+extern crate alloc;
+
+pub mod datatypes;
+
+use alloc::{string::String, vec::Vec};
+use datatypes::Deserialize;
+use sdk_macro::{data_enum, datatype};
+
+#[data_enum(ty = u16)]
+pub enum Kind {
+    A = 1,
+    B = 2,
+}
+
+#[datatype(view)]
+#[derive(Debug, PartialEq)]
+pub struct Reading {
+    pub kind: Kind,
+    #[field(scale = 0.1, raw = i16)]
+    pub temperature: f32,
+    #[field(float = ibm32)]
+    pub ibm: f64,
+    #[field(count = 2)]
+    pub axes: [u16; 2],
+    #[field(length_ty = u8)]
+    pub samples: Vec<u16>,
+    #[field(string = terminated)]
+    pub name: String,
+    #[field(checksum = crc16_ccitt)]
+    pub crc: u16,
+}
+
+#[datatype(pod)]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Sample {
+    pub id: u16,
+    #[field(endianness = LittleEndian)]
+    pub value: u32,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Batch<'a> {
+    #[field(string = prefixed(u8))]
+    pub label: &'a str,
+    #[field(length_ty = u8)]
+    pub samples: Vec<Sample>,
+    #[field(range = 0..10)]
+    pub level: u8,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Bounded {
+    #[field(length_ty = u8)]
+    pub samples: heapless::Vec<u16, 32>,
+    #[field(string = prefixed(u8), encoding = latin1)]
+    pub label: heapless::String<16>,
+    #[field(byte_len_ty = u8)]
+    pub rest: heapless::Vec<u8, 4>,
+}
+
+pub mod imported {
+    use heapless::Vec;
+    use sdk_macro::datatype;
+
+    // the container is named as it is imported
+    #[datatype]
+    #[derive(Debug, PartialEq)]
+    pub struct Short {
+        #[field(length_ty = u8)]
+        pub samples: Vec<u16, 4>,
+        #[field(calc = self.samples.len() as u8)]
+        pub count: u8,
+    }
+}
+
+#[datatype]
+#[derive(Debug, Default, PartialEq)]
+pub struct Point {
+    pub x: i16,
+    pub y: i16,
+}
+
+// only fields that do not allocate, checked with a counting allocator in tests/
+#[datatype(magic = b"FR")]
+#[derive(Debug, PartialEq)]
+pub struct Frame {
+    #[field(range = 0..10)]
+    pub level: u8,
+    pub count: u8,
+    #[field(count = count, padding = 20)]
+    pub points: [Point; 2],
+    #[field(bcd)]
+    pub id: u16,
+}
@@ -0,0 +1,67 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use sdk_macro_no_std::{datatypes::io::ErrorKind, Frame, Point};
+
+// counts the allocations of the current thread, so that the test harness running on other threads
+// does not interfere
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+#[test]
+fn no_allocations() {
+    let frame = Frame { level: 3, count: 2, points: [Point { x: 1, y: -1 }, Point { x: 2, y: -2 }], id: 1234 };
+    let mut buffer = [0u8; 64];
+
+    let count = allocations(|| {
+        let written = frame.serialize_into(&mut buffer).unwrap();
+        assert_eq!(written, 34);
+        assert_eq!(buffer[4..24], [0u8; 20]);
+        let mut bytes = [0u8; 34];
+        bytes.copy_from_slice(&buffer[..written]);
+        assert_eq!(Frame::from_bytes(&bytes).unwrap().0, frame);
+
+        let invalid = Frame { level: 30, count: 2, points: Default::default(), id: 0 };
+        assert_eq!(invalid.validate().unwrap_err().kind(), ErrorKind::InvalidData);
+        assert_eq!(Frame { id: 10000, ..invalid }.serialize_into(&mut buffer).unwrap_err().kind(), ErrorKind::InvalidInput);
+
+        let corrupt = |index: usize, value: u8| {
+            let mut bytes = bytes;
+            bytes[index] = value;
+            Frame::from_bytes(&bytes).unwrap_err()
+        };
+        // magic, range, length of the array and BCD digit
+        assert_eq!(corrupt(0, b'X').kind(), ErrorKind::InvalidData);
+        assert_eq!(corrupt(2, 30).kind(), ErrorKind::InvalidData);
+        assert_eq!(corrupt(3, 3).message(), "'points' does not have a length of 2");
+        assert_eq!(corrupt(32, 0xFF).kind(), ErrorKind::InvalidData);
+        assert_eq!(Frame::from_bytes(&bytes[..20]).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    });
+
+    assert_eq!(count, 0);
+}
@@ -0,0 +1,36 @@
+use sdk_macro_no_std::{
+    datatypes::{io::ErrorKind, Decoded},
+    imported::Short,
+    Batch, Bounded, Kind, Reading, ReadingView, Sample,
+};
+
+#[test]
+fn round_trip() {
+    let reading = Reading { kind: Kind::B, temperature: 21.5, ibm: -118.625, axes: [1, 2], samples: vec![3, 4], name: "probe".into() };
+    let bytes = reading.to_bytes();
+    assert_eq!(Reading::from_bytes(&bytes).unwrap().0, reading);
+    assert_eq!(ReadingView::new(&bytes).temperature().unwrap(), 21.5);
+    assert_eq!(Reading::decode(&bytes[..3]).unwrap(), Decoded::Incomplete { needed: Some(1) });
+
+    let batch = Batch { label: "b", samples: vec![Sample { id: 1, value: 2 }, Sample { id: 3, value: 4 }], level: 3 };
+    let mut buffer = [0u8; 32];
+    let written = batch.serialize_into(&mut buffer).unwrap();
+    assert_eq!(Batch::from_bytes(&buffer[..written]).unwrap(), (batch, written));
+    assert_eq!(Batch { label: "", samples: vec![], level: 30 }.validate().unwrap_err().kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn bounded() {
+    let value = Bounded {
+        samples: heapless::Vec::from_slice(&[1, 2]).unwrap(),
+        label: "Müller".try_into().unwrap(),
+        rest: heapless::Vec::from_slice(&[9]).unwrap(),
+    };
+    let bytes = value.to_bytes();
+    assert_eq!(Bounded::from_bytes(&bytes).unwrap(), (value, bytes.len()));
+
+    let short = Short { samples: heapless::Vec::from_slice(&[7]).unwrap(), count: 1 };
+    let bytes = short.to_bytes();
+    assert_eq!(Short::from_bytes(&bytes).unwrap().0, short);
+    assert_eq!(Short::from_bytes(&[5, 0, 1, 0, 2, 0, 3, 0, 4, 0, 5, 5]).unwrap_err().kind(), ErrorKind::InvalidData);
+}
@@ -0,0 +1,96 @@
+mod common;
+
+pub use common::datatypes;
+use common::{deserialize, serialize, serialize_after};
+use datatypes::Deserialize;
+use sdk_macro::datatype;
+use std::io::{Cursor, ErrorKind};
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Resource {
+    kind: u8,
+    name_offset: u32,
+    #[field(at = name_offset)]
+    name: String,
+    #[field(offset_ty = u16)]
+    data: Vec<u8>,
+    flags: u8,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Streamed {
+    kind: u8,
+    #[field(offset_ty = u8, relative_to = stream, length_ty = u8)]
+    data: Vec<u8>,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Far {
+    kind: u8,
+    value_offset: u64,
+    #[field(at = value_offset)]
+    value: u8,
+    delta: i16,
+    #[field(at = delta)]
+    other: u8,
+}
+
+#[test]
+fn round_trip() {
+    let value = Resource { kind: 1, name_offset: 0, name: "ab".into(), data: vec![7, 8], flags: 9 };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, [1, 0, 0, 0, 8, 0, 11, 9, b'a', b'b', 0, 0, 2, 7, 8]);
+
+    // the offset is filled in on serialization
+    assert_eq!(deserialize::<Resource>(&bytes).unwrap(), Resource { name_offset: 8, ..value });
+}
+
+#[test]
+fn arbitrary_order() {
+    // the data is placed in front of the name
+    let bytes = [1, 0, 0, 0, 13, 0, 8, 9, 0, 2, 7, 8, 0xff, b'a', b'b', 0];
+    let mut byte_stream = Cursor::new(&bytes[..]);
+    let value = Resource::deserialize(&mut byte_stream).unwrap();
+    assert_eq!(value, Resource { kind: 1, name_offset: 13, name: "ab".into(), data: vec![7, 8], flags: 9 });
+
+    // the stream is placed behind the furthest field
+    assert_eq!(byte_stream.position(), 16);
+}
+
+#[test]
+fn relative_to_stream() {
+    let value = Streamed { kind: 1, data: vec![5] };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, [1, 2, 1, 5]);
+
+    // the offset includes the position of the datatype within the stream
+    let bytes = serialize_after(&[0xff; 4], &value).unwrap();
+    assert_eq!(bytes[4..], [1, 6, 1, 5]);
+
+    let mut byte_stream = Cursor::new(&bytes[..]);
+    byte_stream.set_position(4);
+    assert_eq!(Streamed::deserialize(&mut byte_stream).unwrap(), value);
+}
+
+#[test]
+fn hostile_offsets() {
+    // an offset that overflows the position behind the start of the datatype
+    let bytes = [0xff, 1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0, 0];
+    let mut byte_stream = Cursor::new(&bytes[..]);
+    byte_stream.set_position(1);
+    assert_eq!(Far::deserialize(&mut byte_stream).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // an offset far beyond the input
+    let bytes = [1, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0, 0];
+    assert_eq!(deserialize::<Far>(&bytes).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    // a negative offset
+    let bytes = [1, 0, 0, 0, 0, 0, 0, 0, 13, 0xff, 0xfe, 0, 0, 7];
+    assert_eq!(deserialize::<Far>(&bytes).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    let bytes = [1, 0, 0, 0, 0, 0, 0, 0, 13, 0, 12, 0, 8, 7];
+    assert_eq!(deserialize::<Far>(&bytes).unwrap(), Far { kind: 1, value_offset: 13, value: 7, delta: 12, other: 8 });
+}
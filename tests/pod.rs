@@ -0,0 +1,105 @@
+mod common;
+
+pub use common::datatypes;
+use common::{deserialize, serialize};
+use datatypes::Deserialize;
+use sdk_macro::datatype;
+
+// type with a hand-written Deserialize implementation used as a collection element
+#[derive(Debug, PartialEq)]
+pub struct Custom(u8);
+
+impl datatypes::WriteTo for Custom {
+    fn write_to<W: std::io::Write + std::io::Seek>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&[self.0])
+    }
+}
+
+impl datatypes::Serialize for Custom {
+    fn serialize(&self, writer: &mut std::io::BufWriter<std::fs::File>) -> std::io::Result<()> {
+        datatypes::WriteTo::write_to(self, writer)
+    }
+
+    fn size(&self) -> u32 {
+        1
+    }
+}
+
+impl Deserialize for Custom {
+    fn deserialize(byte_stream: &mut std::io::Cursor<&[u8]>) -> std::io::Result<Self> {
+        let mut byte = [0u8];
+        std::io::Read::read_exact(byte_stream, &mut byte)?;
+        Ok(Custom(byte[0]))
+    }
+}
+
+#[datatype(pod)]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Sample {
+    #[field(range = 0..1000)]
+    id: u16,
+    #[field(endianness = LittleEndian)]
+    value: f32,
+    flags: u8,
+    #[field(count = 3)]
+    axes: [i16; 3],
+    #[field(endianness = LittleEndian)]
+    stamp: u64,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Time {
+    seconds: u8,
+    #[field(string = prefixed(u8))]
+    zone: String,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Batch {
+    count: u16,
+    #[field(count = count)]
+    samples: Vec<Sample>,
+    #[field(length_ty = u8)]
+    times: Vec<Time>,
+    #[field(length_ty = u8)]
+    customs: Vec<Custom>,
+}
+
+fn sample() -> Sample {
+    Sample { id: 7, value: 1.5, flags: 3, axes: [1, -2, 300], stamp: 0x0102030405060708 }
+}
+
+#[test]
+fn layout() {
+    assert_eq!(Sample::SIZE, 21);
+    assert_eq!(std::mem::size_of::<Sample>(), 21);
+
+    let bytes = sample().to_bytes();
+    assert_eq!(bytes, [0, 7, 0, 0, 0xc0, 0x3f, 3, 0, 1, 0xff, 0xfe, 1, 44, 8, 7, 6, 5, 4, 3, 2, 1]);
+    assert_eq!(Sample::from_bytes(&bytes).unwrap(), (sample(), 21));
+}
+
+#[test]
+fn collections() {
+    // pod elements are read in bulk, all others one by one through Deserialize
+    let value = Batch {
+        count: 2,
+        samples: vec![sample(), Sample { id: 9, ..sample() }],
+        times: vec![Time { seconds: 1, zone: "utc".into() }],
+        customs: vec![Custom(5), Custom(6)],
+    };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes.len(), 2 + 42 + 1 + 5 + 1 + 2);
+    assert_eq!(deserialize::<Batch>(&bytes).unwrap(), value);
+    assert!(deserialize::<Batch>(&bytes[..43]).is_err());
+}
+
+#[test]
+fn constraints() {
+    let mut bytes = sample().to_bytes();
+    bytes[0] = 0xff;
+    assert!(Sample::from_bytes(&bytes).is_err());
+    assert!(Sample { id: 2000, ..sample() }.validate().is_err());
+}
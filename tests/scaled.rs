@@ -0,0 +1,63 @@
+mod common;
+
+pub use common::datatypes;
+use common::{deserialize, serialize};
+use sdk_macro::datatype;
+use std::io::ErrorKind;
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Measurement {
+    #[field(raw = i16, scale = 0.01, offset = -40.0)]
+    temperature: f32,
+    #[field(fixed = q16_16)]
+    position: f64,
+    #[field(fixed = q15)]
+    gain: f32,
+    #[field(fixed = uq8_8, endianness = LittleEndian)]
+    ratio: f64,
+}
+
+fn measurement() -> Measurement {
+    Measurement { temperature: 21.5, position: -2.25, gain: 0.5, ratio: 1.5 }
+}
+
+#[test]
+fn round_trip() {
+    let value = measurement();
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, [0x18, 0x06, 0xff, 0xfd, 0xc0, 0x00, 0x40, 0x00, 0x80, 0x01]);
+    assert_eq!(deserialize::<Measurement>(&bytes).unwrap(), value);
+
+    let value = Measurement { temperature: -40.0, position: 32767.0, gain: -1.0, ratio: 0.0 };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, [0, 0, 0x7f, 0xff, 0, 0, 0x80, 0x00, 0, 0]);
+    assert_eq!(deserialize::<Measurement>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn rounding() {
+    // rounded to the nearest raw value, halfway cases away from zero
+    let bytes = serialize(&Measurement { temperature: 21.504, position: 1.0 / 3.0, ..measurement() }).unwrap();
+    assert_eq!(&bytes[..2], &[0x18, 0x06]);
+    assert_eq!(&bytes[2..6], &[0x00, 0x00, 0x55, 0x55]);
+
+    let bytes = serialize(&Measurement { ratio: 1.5 / 256.0 + 1.0 / 512.0, ..measurement() }).unwrap();
+    assert_eq!(&bytes[8..], &[0x02, 0x00]);
+    let bytes = serialize(&Measurement { ratio: -1.0 / 1024.0, ..measurement() }).unwrap();
+    assert_eq!(&bytes[8..], &[0x00, 0x00]);
+}
+
+#[test]
+fn out_of_range() {
+    for value in [
+        Measurement { temperature: 400.0, ..measurement() },
+        Measurement { temperature: f32::NAN, ..measurement() },
+        Measurement { position: 32768.0, ..measurement() },
+        Measurement { gain: 1.0, ..measurement() },
+        Measurement { ratio: -0.01, ..measurement() },
+        Measurement { ratio: f64::INFINITY, ..measurement() },
+    ] {
+        assert_eq!(serialize(&value).unwrap_err().kind(), ErrorKind::InvalidInput, "{:?}", value);
+    }
+}
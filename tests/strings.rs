@@ -0,0 +1,66 @@
+mod common;
+
+pub use common::datatypes;
+use common::{deserialize, serialize};
+use sdk_macro::datatype;
+use std::io::ErrorKind;
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Device {
+    a: String,
+    #[field(string = prefixed(u8))]
+    b: String,
+    #[field(string = fixed(6), pad = b' ')]
+    c: String,
+    #[field(string = fixed(4), truncate)]
+    d: String,
+    #[field(string = terminated(0x0A))]
+    e: String,
+    #[field(string = prefixed(varint))]
+    f: String,
+    #[field(string = prefixed(u16), endianness = LittleEndian)]
+    g: String,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Long {
+    #[field(string = prefixed(u32))]
+    a: String,
+}
+
+fn device() -> Device {
+    Device { a: "hi".into(), b: "abc".into(), c: "xy".into(), d: "trunc".into(), e: "line".into(), f: "é".into(), g: "z".into() }
+}
+
+#[test]
+fn round_trip() {
+    let value = device();
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, b"hi\0\x03abcxy    trunline\n\x02\xc3\xa9\x01\x00z");
+
+    // truncated strings come back shortened
+    assert_eq!(deserialize::<Device>(&bytes).unwrap(), Device { d: "trun".into(), ..value });
+}
+
+#[test]
+fn invalid() {
+    assert!(serialize(&Device { c: "waytoolong".into(), ..device() }).is_err());
+    assert!(serialize(&Device { e: "two\nlines".into(), ..device() }).is_err());
+
+    assert_eq!(deserialize::<Device>(b"hi").unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    let mut bytes = serialize(&device()).unwrap();
+    bytes[4] = 0xff;
+    assert!(deserialize::<Device>(&bytes).is_err());
+}
+
+#[test]
+fn prefix_longer_than_input() {
+    // the length is checked against the remaining input before anything is allocated
+    let input = [0xff, 0xff, 0xff, 0xf0, b'a'];
+    assert_eq!(deserialize::<Long>(&input).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    let input = [0, 0, 0, 1, b'a'];
+    assert_eq!(deserialize::<Long>(&input).unwrap().a, "a");
+}
@@ -0,0 +1,67 @@
+mod common;
+
+pub use common::datatypes;
+use common::{deserialize, serialize};
+use sdk_macro::datatype;
+use std::io::ErrorKind;
+
+#[datatype(endianness = LittleEndian)]
+#[derive(Debug, PartialEq)]
+pub struct Little {
+    a: u16,
+    #[field(endianness = BigEndian)]
+    b: u16,
+    #[field(length_ty = u16)]
+    c: Vec<u32>,
+}
+
+#[datatype(serialize)]
+#[derive(Debug, PartialEq)]
+pub struct Request {
+    id: u8,
+    #[field(string = prefixed(u8))]
+    name: String,
+}
+
+#[datatype(deserialize)]
+#[derive(Debug, PartialEq)]
+pub struct Response {
+    id: u8,
+    status: u16,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Short {
+    #[field(length_ty = u8)]
+    data: Vec<u8>,
+}
+
+#[test]
+fn endianness() {
+    // the datatype sets the default, fields can still choose their own
+    let value = Little { a: 1, b: 2, c: vec![3] };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, [1, 0, 0, 2, 1, 0, 3, 0, 0, 0]);
+    assert_eq!(deserialize::<Little>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn directions() {
+    let bytes = serialize(&Request { id: 1, name: "a".into() }).unwrap();
+    assert_eq!(bytes, [1, 1, b'a']);
+    assert_eq!(Request { id: 1, name: "a".into() }.to_bytes(), bytes);
+
+    assert_eq!(deserialize::<Response>(&[1, 0, 2]).unwrap(), Response { id: 1, status: 2 });
+    assert_eq!(Response::from_bytes(&[1, 0, 2]).unwrap(), (Response { id: 1, status: 2 }, 3));
+}
+
+#[test]
+fn length_too_long() {
+    // the length is checked instead of being truncated
+    let value = Short { data: vec![0; 256] };
+    assert_eq!(value.try_to_bytes().unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    let value = Short { data: vec![7; 255] };
+    assert_eq!(value.to_bytes()[..2], [255, 7]);
+}
@@ -0,0 +1,40 @@
+mod common;
+
+pub use common::datatypes;
+use common::{deserialize, serialize};
+use sdk_macro::datatype;
+use std::io::ErrorKind;
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Path {
+    #[field(until = 0)]
+    segments: Vec<u16>,
+    #[field(until_eof)]
+    trailer: Vec<u8>,
+}
+
+#[test]
+fn round_trip() {
+    let value = Path { segments: vec![1, 0x0203], trailer: vec![7, 8, 9] };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, [0, 1, 2, 3, 0, 0, 7, 8, 9]);
+    assert_eq!(deserialize::<Path>(&bytes).unwrap(), value);
+
+    let value = Path { segments: vec![], trailer: vec![] };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, [0, 0]);
+    assert_eq!(deserialize::<Path>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn missing_terminator() {
+    assert_eq!(deserialize::<Path>(&[0, 1, 0, 2]).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(deserialize::<Path>(&[0, 1, 0]).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn contains_terminator() {
+    let error = serialize(&Path { segments: vec![1, 0, 2], trailer: vec![] }).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+}
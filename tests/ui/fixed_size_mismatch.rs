@@ -0,0 +1,34 @@
+use sdk_macro::datatype;
+
+pub mod datatypes {
+    pub trait Serialize {
+        fn serialize(&self, writer: &mut std::io::BufWriter<std::fs::File>) -> std::io::Result<()>;
+        fn size(&self) -> u32;
+    }
+
+    pub trait WriteTo {
+        fn write_to<W: std::io::Write + std::io::Seek>(&self, writer: &mut W) -> std::io::Result<()>;
+    }
+
+    pub trait Deserialize {
+        fn deserialize(byte_stream: &mut std::io::Cursor<&[u8]>) -> std::io::Result<Self> where Self: Sized;
+    }
+
+    pub trait FixedSize {
+        const SIZE: usize;
+    }
+
+    pub enum Decoded<T> {
+        Complete(T, usize),
+        Incomplete { needed: Option<usize> },
+    }
+}
+
+#[datatype(fixed_size = 32)]
+pub struct Time {
+    seconds: u8,
+    minutes: u8,
+    hours: u8,
+}
+
+fn main() {}
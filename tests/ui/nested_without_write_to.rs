@@ -0,0 +1,55 @@
+use sdk_macro::datatype;
+use datatypes::{Deserialize, Serialize};
+
+pub mod datatypes {
+    pub trait Serialize {
+        fn serialize(&self, writer: &mut std::io::BufWriter<std::fs::File>) -> std::io::Result<()>;
+        fn size(&self) -> u32;
+    }
+
+    pub trait WriteTo {
+        fn write_to<W: std::io::Write + std::io::Seek>(&self, writer: &mut W) -> std::io::Result<()>;
+    }
+
+    pub trait Deserialize {
+        fn deserialize(byte_stream: &mut std::io::Cursor<&[u8]>) -> std::io::Result<Self> where Self: Sized;
+    }
+
+    pub trait FixedSize {
+        const SIZE: usize;
+    }
+
+    pub enum Decoded<T> {
+        Complete(T, usize),
+        Incomplete { needed: Option<usize> },
+    }
+}
+
+// only implements Serialize, nested types must be writable to any writer
+pub struct Custom(u8);
+
+impl Serialize for Custom {
+    fn serialize(&self, writer: &mut std::io::BufWriter<std::fs::File>) -> std::io::Result<()> {
+        std::io::Write::write_all(writer, &[self.0])
+    }
+
+    fn size(&self) -> u32 {
+        1
+    }
+}
+
+impl Deserialize for Custom {
+    fn deserialize(byte_stream: &mut std::io::Cursor<&[u8]>) -> std::io::Result<Self> {
+        let mut byte = [0u8];
+        std::io::Read::read_exact(byte_stream, &mut byte)?;
+        Ok(Custom(byte[0]))
+    }
+}
+
+#[datatype]
+pub struct Outer {
+    kind: u8,
+    custom: Custom,
+}
+
+fn main() {}
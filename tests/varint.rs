@@ -0,0 +1,105 @@
+mod common;
+
+pub use common::datatypes;
+use common::{deserialize, serialize};
+use sdk_macro::datatype;
+use std::io::ErrorKind;
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Time {
+    seconds: u8,
+    minutes: u16,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Deltas {
+    #[field(varint)]
+    a: u32,
+    #[field(varint)]
+    b: i32,
+    #[field(zigzag)]
+    c: i64,
+    #[field(varint = vlq)]
+    d: u64,
+    #[field(length_ty = varint)]
+    e: Vec<Time>,
+    #[field(varint)]
+    f: u128,
+    #[field(varint)]
+    g: i128,
+}
+
+#[datatype]
+#[derive(Debug, PartialEq)]
+pub struct Lengths {
+    #[field(length_ty = varint)]
+    a: Vec<u16>,
+    #[field(length_ty = vlq)]
+    b: Vec<Time>,
+}
+
+#[test]
+fn round_trip() {
+    let value = Deltas {
+        a: 300,
+        b: -129,
+        c: -3,
+        d: 0x4000,
+        e: (0..200).map(|i| Time { seconds: i as u8, minutes: 3 }).collect(),
+        f: u128::MAX,
+        g: i128::MIN,
+    };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(&bytes[..2], &[0xac, 0x02]);
+    assert_eq!(&bytes[2..4], &[0xff, 0x7e]);
+    assert_eq!(&bytes[4..5], &[5]);
+    assert_eq!(&bytes[5..8], &[0x81, 0x80, 0x00]);
+    assert_eq!(&bytes[8..10], &[0xc8, 0x01]);
+    assert_eq!(deserialize::<Deltas>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn edge_values() {
+    let value = Deltas { a: 0, b: 0, c: 0, d: 0, e: vec![], f: 0, g: 0 };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, [0; 7]);
+    assert_eq!(deserialize::<Deltas>(&bytes).unwrap(), value);
+
+    let value = Deltas { a: u32::MAX, b: i32::MIN, c: i64::MAX, d: u64::MAX, e: vec![], f: 127, g: -64 };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(&bytes[..5], &[0xff, 0xff, 0xff, 0xff, 0x0f]);
+    assert_eq!(&bytes[5..10], &[0x80, 0x80, 0x80, 0x80, 0x78]);
+    assert_eq!(deserialize::<Deltas>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn malformed() {
+    // more bytes than a u32 can have
+    let input = [0x80, 0x80, 0x80, 0x80, 0x80, 0x01];
+    assert!(deserialize::<Deltas>(&input).is_err());
+
+    // fits into five bytes but not into a u32
+    let input = [0xff, 0xff, 0xff, 0xff, 0x7f];
+    assert!(deserialize::<Deltas>(&input).is_err());
+
+    // continuation bit set on the last byte of the input
+    let input = [0x80];
+    assert_eq!(deserialize::<Deltas>(&input).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn huge_length() {
+    // a length close to u64::MAX must fail on the missing input instead of preallocating it
+    let input = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+    assert_eq!(deserialize::<Lengths>(&input).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    let input = [0x00, 0x81, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f];
+    assert_eq!(deserialize::<Lengths>(&input).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    let value = Lengths { a: vec![1, 2], b: vec![Time { seconds: 1, minutes: 2 }] };
+    let bytes = serialize(&value).unwrap();
+    assert_eq!(bytes, [2, 0, 1, 0, 2, 1, 1, 0, 2]);
+    assert_eq!(deserialize::<Lengths>(&bytes).unwrap(), value);
+}
@@ -0,0 +1,90 @@
+mod common;
+
+pub use common::datatypes;
+use common::{deserialize, serialize};
+use sdk_macro::datatype;
+
+#[datatype(view, magic = b"V")]
+#[derive(Debug, PartialEq)]
+pub struct Record {
+    id: u32,
+    #[field(endianness = LittleEndian)]
+    temp: i16,
+    #[field(string = prefixed(u8))]
+    name: String,
+    #[field(length_ty = u8)]
+    values: Vec<u16>,
+    #[field(range = 0..100)]
+    level: u8,
+    #[field(raw = i16, scale = 0.5)]
+    scaled: f32,
+    #[field(checksum = crc32, over = (..=temp))]
+    crc: u32,
+    #[field(string = terminated)]
+    tail: String,
+}
+
+#[datatype(view)]
+#[derive(Debug, PartialEq)]
+pub struct Borrowed<'a> {
+    a: u8,
+    #[field(string = prefixed(u8))]
+    name: &'a str,
+    b: u16,
+}
+
+fn record() -> Record {
+    Record { id: 7, temp: -3, name: "abc".into(), values: vec![1, 2], level: 5, scaled: 1.5, tail: "t".into() }
+}
+
+#[test]
+fn getters() {
+    let bytes = serialize(&record()).unwrap();
+    let view = RecordView::new(&bytes);
+    assert_eq!(view.id().unwrap(), 7);
+    assert_eq!(view.temp().unwrap(), -3);
+    assert_eq!(view.name().unwrap(), "abc");
+    assert_eq!(view.values().unwrap(), vec![1, 2]);
+    assert_eq!(view.level().unwrap(), 5);
+    assert_eq!(view.scaled().unwrap(), 1.5);
+    assert_eq!(view.tail().unwrap(), "t");
+
+    // fields behind the end of a truncated input fail, the ones before it can still be read
+    let view = RecordView::new(&bytes[..6]);
+    assert_eq!(view.id().unwrap(), 7);
+    assert_eq!(view.temp().unwrap_err().kind(), std::io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn setters() {
+    let mut bytes = serialize(&record()).unwrap();
+    let mut view = RecordViewMut::new(&mut bytes);
+    view.set_id(9).unwrap();
+    view.set_temp(12).unwrap();
+    view.set_level(7).unwrap();
+    assert_eq!(view.view().id().unwrap(), 9);
+
+    // the checksum over id and temp is updated along with them
+    assert_eq!(deserialize::<Record>(&bytes).unwrap(), Record { id: 9, temp: 12, level: 7, ..record() });
+
+    // values violating a constraint and writes behind the end leave the input untouched
+    let before = bytes.clone();
+    assert!(RecordViewMut::new(&mut bytes).set_level(200).is_err());
+    assert_eq!(bytes, before);
+    let mut short = bytes[..3].to_vec();
+    assert!(RecordViewMut::new(&mut short).set_temp(1).is_err());
+    assert_eq!(short, bytes[..3]);
+}
+
+#[test]
+fn borrowed() {
+    let bytes = serialize(&Borrowed { a: 1, name: "xy", b: 3 }).unwrap();
+    let view = BorrowedView::new(&bytes);
+    let name: &str = view.name().unwrap();
+    assert_eq!(name, "xy");
+    assert_eq!(view.b().unwrap(), 3);
+
+    let mut bytes = bytes.clone();
+    BorrowedViewMut::new(&mut bytes).set_b(5).unwrap();
+    assert_eq!(Borrowed::from_bytes(&bytes).unwrap().0, Borrowed { a: 1, name: "xy", b: 5 });
+}